@@ -276,6 +276,14 @@ export const DroneInfo = defineComponent({
                 :value="'X: ' + drone.x_speed + ', Y: ' + drone.y_speed + ', Z: ' + drone.z_speed"
             />
             <LabelAndValue v-if="drone.rotation" label="Rotation" :value="roundIt(drone.rotation) + '°'"/>
+            <LabelAndValue v-if="drone.radio && drone.radio.signal != null" label="Signal" :value="drone.radio.signal + ' dBm' + (drone.radio.frequency ? ' @ ' + drone.radio.frequency + ' MHz' : '')"/>
+            <LabelAndValue v-if="drone.manufacturer" label="Manufacturer" :value="drone.manufacturer"/>
+            <LabelAndValue v-if="drone.serial_number_error" label="Serial Number" value="Malformed"/>
+            <LabelAndValue v-if="drone.operator_id" label="Operator ID" :value="drone.operator_id + (drone.operator_id_status === 'ValidFormat' ? ' (valid format)' : drone.operator_id_status === 'ChecksumUnverified' ? ' (checksum unverified)' : drone.operator_id_status === 'InvalidChecksum' ? ' (invalid checksum)' : ' (unknown scheme)')"/>
+            <LabelAndValue v-if="drone.violations && drone.violations.length" label="Violations" :value="drone.violations.map(violation => typeof violation === 'string' ? violation : Object.keys(violation)[0]).join(', ')"/>
+            <LabelAndValue v-if="drone.self_id" label="Self ID" :value="drone.self_id.description"/>
+            <LabelAndValue v-if="drone.classification_type === 'EuropeanUnion'" label="EU Category" :value="drone.ua_category"/>
+            <LabelAndValue v-if="drone.classification_type === 'EuropeanUnion'" label="EU Class" :value="drone.ua_class"/>
             <Actions :drone="drone" :with-info="withInfo" />
             <Options :drone="drone" />
             <slot />
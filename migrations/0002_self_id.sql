@@ -0,0 +1,3 @@
+ALTER TABLE drones
+    ADD COLUMN self_id_type SMALLINT,
+    ADD COLUMN self_id_description VARCHAR(23);
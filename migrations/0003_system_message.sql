@@ -0,0 +1,6 @@
+ALTER TABLE drones
+    ADD COLUMN ua_classification_type SMALLINT,
+    ADD COLUMN ua_category SMALLINT,
+    ADD COLUMN ua_class SMALLINT,
+    ADD COLUMN operator_altitude FLOAT8,
+    ADD COLUMN system_timestamp TIMESTAMPTZ;
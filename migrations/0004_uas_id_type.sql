@@ -0,0 +1,3 @@
+ALTER TABLE drones
+    ADD COLUMN uas_id_type SMALLINT,
+    ADD COLUMN session_id_type SMALLINT;
@@ -0,0 +1,3 @@
+ALTER TABLE drones
+    ADD COLUMN operator_id_type SMALLINT,
+    ADD COLUMN operator_id VARCHAR(20);
@@ -0,0 +1,2 @@
+ALTER TABLE drones
+    ADD COLUMN violations TEXT;
@@ -0,0 +1,4 @@
+ALTER TABLE drones
+    ADD COLUMN transmitted TIMESTAMPTZ,
+    ADD COLUMN received TIMESTAMPTZ,
+    ADD COLUMN timestamp_accuracy FLOAT8;
@@ -0,0 +1,7 @@
+ALTER TABLE drones
+    ADD COLUMN rssi SMALLINT,
+    ADD COLUMN noise SMALLINT,
+    ADD COLUMN frequency INTEGER,
+    ADD COLUMN radio_flags SMALLINT,
+    ADD COLUMN antenna SMALLINT,
+    ADD COLUMN tsft BIGINT;
@@ -0,0 +1,139 @@
+/* Generated by cbindgen from odid-ffi/src/lib.rs, do not edit. */
+
+#ifndef ODID_H
+#define ODID_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define ODID_UAS_ID_SIZE 39
+
+#define ODID_OPERATOR_ID_SIZE 21
+
+#define ODID_SELF_ID_DESCRIPTION_SIZE 24
+
+typedef enum OdidStatus {
+  ODID_STATUS_OK = 0,
+  ODID_STATUS_NULL_POINTER = 1,
+  ODID_STATUS_TOO_SHORT = 2,
+  ODID_STATUS_UNEXPECTED_TYPE = 3,
+  ODID_STATUS_INVALID_LENGTH = 4,
+  ODID_STATUS_INVALID_VALUE = 5,
+} OdidStatus;
+
+/**
+ * Mirrors `BasicId`. `session_id_type` is 0 unless `uas_id_type` is a Specific Session ID.
+ */
+typedef struct OdidBasicId {
+  uint8_t uas_id_type;
+  uint8_t ua_type;
+  uint8_t session_id_type;
+  char uas_id[ODID_UAS_ID_SIZE];
+} OdidBasicId;
+
+/**
+ * Mirrors `Location`, with the fields still in their broadcast encoding.
+ */
+typedef struct OdidLocation {
+  uint8_t status;
+  uint8_t height_type;
+  uint8_t ew_direction;
+  uint8_t speed_multiplier;
+  uint8_t tracking_direction;
+  uint8_t speed;
+  int8_t vertical_speed;
+  int32_t latitude_int;
+  int32_t longitude_int;
+  uint16_t altitude_pressure;
+  uint16_t altitude_geodetic;
+  uint16_t height;
+  uint8_t horizontal_accuracy;
+  uint8_t vertical_accuracy;
+  uint8_t barometric_altitude_accuracy;
+  uint8_t speed_accuracy;
+  uint16_t timestamp;
+  uint8_t timestamp_accuracy;
+} OdidLocation;
+
+/**
+ * Mirrors `SystemMessage`, with the fields still in their broadcast encoding.
+ */
+typedef struct OdidSystemMessage {
+  uint8_t operator_location_type;
+  uint8_t classification_type;
+  int32_t operator_latitude_int;
+  int32_t operator_longitude_int;
+  uint16_t area_count;
+  uint8_t area_radius;
+  uint16_t area_ceiling;
+  uint16_t area_floor;
+  uint8_t ua_category;
+  uint8_t ua_class;
+  uint16_t operator_altitude_geodetic;
+  uint32_t timestamp;
+} OdidSystemMessage;
+
+typedef struct OdidOperator {
+  uint8_t operator_id_type;
+  char operator_id[ODID_OPERATOR_ID_SIZE];
+} OdidOperator;
+
+typedef struct OdidSelfId {
+  uint8_t description_type;
+  char description[ODID_SELF_ID_DESCRIPTION_SIZE];
+} OdidSelfId;
+
+/**
+ * The last message of each type decoded for one drone.
+ */
+typedef struct OdidUasData {
+  bool has_basic_id;
+  struct OdidBasicId basic_id;
+  bool has_location;
+  struct OdidLocation location;
+  bool has_system_message;
+  struct OdidSystemMessage system_message;
+  bool has_operator_id;
+  struct OdidOperator operator_id;
+  bool has_self_id;
+  struct OdidSelfId self_id;
+} OdidUasData;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Clears every member of `uas_data`.
+ */
+void odid_uas_data_init(struct OdidUasData *uas_data);
+
+/**
+ * Decodes a raw 25 byte message, or a message pack, header included.
+ */
+enum OdidStatus odid_decode_message(const uint8_t *data,
+                                    size_t length,
+                                    struct OdidUasData *uas_data);
+
+/**
+ * Decodes an 802.11 Beacon or NAN action frame, starting at the frame control field (no
+ * radiotap header, no FCS required).
+ */
+enum OdidStatus odid_decode_wifi_frame(const uint8_t *data,
+                                       size_t length,
+                                       struct OdidUasData *uas_data);
+
+/**
+ * Decodes the AD structures of a Bluetooth advertisement, as reported by the controller.
+ */
+enum OdidStatus odid_decode_bluetooth_frame(const uint8_t *data,
+                                            size_t length,
+                                            struct OdidUasData *uas_data);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ODID_H */
@@ -0,0 +1,357 @@
+//! C ABI for the Open Drone ID decoder. Every decode function fills the members of an
+//! `OdidUasData` for the messages it finds and sets their `has_*` flag, leaving the other
+//! members untouched, so the messages of one drone can be collected across frames.
+//!
+//! Pointers may be null, which is reported as `ODID_STATUS_NULL_POINTER`. Otherwise `data`
+//! must point to `length` readable bytes and `uas_data` to a writable `OdidUasData`.
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::c_char;
+use std::slice;
+
+use odid::bluetooth::parse_bluetooth_advertising_data;
+use odid::wifi::{is_action_frame, is_beacon_frame, parse_beacon_frame, parse_nan_action_frame};
+use odid::{
+    parse_message, parse_message_pack, parse_message_type, BasicId, Location, OdidMessage,
+    Operator, ParseError, ParseErrorReason, ParseLayer, RemoteIdMessage, SelfId, SystemMessage,
+    OPERATOR_ID_LENGTH, SELF_ID_DESCRIPTION_LENGTH, UAS_ID_TEXT_LENGTH,
+};
+
+// sizes of the null terminated text fields; literals so the header gets plain numbers
+pub const ODID_UAS_ID_SIZE: usize = 39;
+pub const ODID_OPERATOR_ID_SIZE: usize = 21;
+pub const ODID_SELF_ID_DESCRIPTION_SIZE: usize = 24;
+
+const _: () = assert!(ODID_UAS_ID_SIZE == UAS_ID_TEXT_LENGTH + 1);
+const _: () = assert!(ODID_OPERATOR_ID_SIZE == OPERATOR_ID_LENGTH + 1);
+const _: () = assert!(ODID_SELF_ID_DESCRIPTION_SIZE == SELF_ID_DESCRIPTION_LENGTH + 1);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OdidStatus {
+    Ok = 0,
+    NullPointer = 1,
+    TooShort = 2,
+    UnexpectedType = 3,
+    InvalidLength = 4,
+    InvalidValue = 5,
+}
+
+impl From<ParseError> for OdidStatus {
+    fn from(error: ParseError) -> Self {
+        match error.reason {
+            ParseErrorReason::TooShort => OdidStatus::TooShort,
+            ParseErrorReason::UnexpectedType => OdidStatus::UnexpectedType,
+            ParseErrorReason::InvalidLength => OdidStatus::InvalidLength,
+            ParseErrorReason::InvalidValue => OdidStatus::InvalidValue,
+        }
+    }
+}
+
+/// Mirrors `BasicId`. `session_id_type` is 0 unless `uas_id_type` is a Specific Session ID.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OdidBasicId {
+    pub uas_id_type: u8,
+    pub ua_type: u8,
+    pub session_id_type: u8,
+    pub uas_id: [c_char; ODID_UAS_ID_SIZE],
+}
+
+/// Mirrors `Location`, with the fields still in their broadcast encoding.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OdidLocation {
+    pub status: u8,
+    pub height_type: u8,
+    pub ew_direction: u8,
+    pub speed_multiplier: u8,
+    pub tracking_direction: u8,
+    pub speed: u8,
+    pub vertical_speed: i8,
+    pub latitude_int: i32,
+    pub longitude_int: i32,
+    pub altitude_pressure: u16,
+    pub altitude_geodetic: u16,
+    pub height: u16,
+    pub horizontal_accuracy: u8,
+    pub vertical_accuracy: u8,
+    pub barometric_altitude_accuracy: u8,
+    pub speed_accuracy: u8,
+    pub timestamp: u16,
+    pub timestamp_accuracy: u8,
+}
+
+/// Mirrors `SystemMessage`, with the fields still in their broadcast encoding.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OdidSystemMessage {
+    pub operator_location_type: u8,
+    pub classification_type: u8,
+    pub operator_latitude_int: i32,
+    pub operator_longitude_int: i32,
+    pub area_count: u16,
+    pub area_radius: u8,
+    pub area_ceiling: u16,
+    pub area_floor: u16,
+    pub ua_category: u8,
+    pub ua_class: u8,
+    pub operator_altitude_geodetic: u16,
+    pub timestamp: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OdidOperator {
+    pub operator_id_type: u8,
+    pub operator_id: [c_char; ODID_OPERATOR_ID_SIZE],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OdidSelfId {
+    pub description_type: u8,
+    pub description: [c_char; ODID_SELF_ID_DESCRIPTION_SIZE],
+}
+
+/// The last message of each type decoded for one drone.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OdidUasData {
+    pub has_basic_id: bool,
+    pub basic_id: OdidBasicId,
+    pub has_location: bool,
+    pub location: OdidLocation,
+    pub has_system_message: bool,
+    pub system_message: OdidSystemMessage,
+    pub has_operator_id: bool,
+    pub operator_id: OdidOperator,
+    pub has_self_id: bool,
+    pub self_id: OdidSelfId,
+}
+
+// copies at most N - 1 bytes so the field stays null terminated
+fn c_string<const N: usize>(text: &str) -> [c_char; N] {
+    let mut field = [0; N];
+
+    for (c, b) in field.iter_mut().zip(text.bytes().take(N - 1)) {
+        *c = b as c_char;
+    }
+
+    field
+}
+
+impl From<&BasicId> for OdidBasicId {
+    fn from(basic_id: &BasicId) -> Self {
+        OdidBasicId {
+            uas_id_type: u8::from(basic_id.uas_id_type.clone()),
+            ua_type: u8::from(basic_id.ua_type.clone()),
+            session_id_type: basic_id
+                .session_id_type
+                .clone()
+                .map(u8::from)
+                .unwrap_or_default(),
+            uas_id: c_string(&basic_id.uas_id),
+        }
+    }
+}
+
+impl From<&Location> for OdidLocation {
+    fn from(location: &Location) -> Self {
+        OdidLocation {
+            status: location.status,
+            height_type: location.height_type,
+            ew_direction: location.ew_direction,
+            speed_multiplier: location.speed_multiplier,
+            tracking_direction: location.tracking_direction,
+            speed: location.speed,
+            vertical_speed: location.vertical_speed,
+            latitude_int: location.latitude_int,
+            longitude_int: location.longitude_int,
+            altitude_pressure: location.altitude_pressure,
+            altitude_geodetic: location.altitude_geodetic,
+            height: location.height,
+            horizontal_accuracy: location.horizontal_accuracy,
+            vertical_accuracy: location.vertical_accuracy,
+            barometric_altitude_accuracy: location.barometric_altitude_accuracy,
+            speed_accuracy: location.speed_accuracy,
+            timestamp: location.timestamp,
+            timestamp_accuracy: location.timestamp_accuracy,
+        }
+    }
+}
+
+impl From<&SystemMessage> for OdidSystemMessage {
+    fn from(system_message: &SystemMessage) -> Self {
+        OdidSystemMessage {
+            operator_location_type: u8::from(system_message.operator_location_type.clone()),
+            classification_type: u8::from(system_message.classification_type.clone()),
+            operator_latitude_int: system_message.operator_latitude_int,
+            operator_longitude_int: system_message.operator_longitude_int,
+            area_count: system_message.area_count,
+            area_radius: system_message.area_radius,
+            area_ceiling: system_message.area_ceiling,
+            area_floor: system_message.area_floor,
+            ua_category: u8::from(system_message.ua_category.clone()),
+            ua_class: u8::from(system_message.ua_class.clone()),
+            operator_altitude_geodetic: system_message.operator_altitude_geodetic,
+            timestamp: system_message.timestamp,
+        }
+    }
+}
+
+impl From<&Operator> for OdidOperator {
+    fn from(operator: &Operator) -> Self {
+        OdidOperator {
+            operator_id_type: operator.operator_id_type,
+            operator_id: c_string(&operator.operator_id),
+        }
+    }
+}
+
+impl From<&SelfId> for OdidSelfId {
+    fn from(self_id: &SelfId) -> Self {
+        OdidSelfId {
+            description_type: u8::from(self_id.description_type.clone()),
+            description: c_string(&self_id.description),
+        }
+    }
+}
+
+impl OdidUasData {
+    fn store(&mut self, message: &OdidMessage) {
+        match message {
+            OdidMessage::BasicId(basic_id) => {
+                self.basic_id = OdidBasicId::from(basic_id);
+                self.has_basic_id = true;
+            }
+            OdidMessage::Location(location) => {
+                self.location = OdidLocation::from(location);
+                self.has_location = true;
+            }
+            OdidMessage::SystemMessage(system_message) => {
+                self.system_message = OdidSystemMessage::from(system_message);
+                self.has_system_message = true;
+            }
+            OdidMessage::OperatorId(operator) => {
+                self.operator_id = OdidOperator::from(operator);
+                self.has_operator_id = true;
+            }
+            OdidMessage::SelfId(self_id) => {
+                self.self_id = OdidSelfId::from(self_id);
+                self.has_self_id = true;
+            }
+            OdidMessage::Authentication(_) | OdidMessage::Unknown(_) => {}
+        }
+    }
+
+    // a single message, or a message pack
+    fn decode(&mut self, input: &[u8]) -> Result<(), ParseError> {
+        match parse_message_type(input)? {
+            (_, RemoteIdMessage::MessagePack) => {
+                let (_, message_pack) = parse_message_pack(input)?;
+
+                for message in &message_pack.messages {
+                    self.store(message);
+                }
+            }
+            _ => {
+                let (_, message) = parse_message(input)?;
+
+                self.store(&message);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// the message pack of a Wi-Fi Beacon or NAN frame
+fn wifi_frame_message_pack(frame: &[u8]) -> Result<&[u8], ParseError> {
+    if is_beacon_frame(frame, 0) {
+        let (_, beacon_frame) = parse_beacon_frame(frame)?;
+
+        return Ok(beacon_frame.vendor_specific_data);
+    }
+
+    if is_action_frame(frame, 0) {
+        let (_, attribute) = parse_nan_action_frame(frame)?;
+
+        return Ok(attribute.service_info);
+    }
+
+    Err(ParseError::new(
+        ParseLayer::Ieee80211,
+        0,
+        ParseErrorReason::UnexpectedType,
+    ))
+}
+
+unsafe fn decode_with<F>(
+    data: *const u8,
+    length: usize,
+    uas_data: *mut OdidUasData,
+    decode: F,
+) -> OdidStatus
+where
+    F: FnOnce(&[u8], &mut OdidUasData) -> Result<(), ParseError>,
+{
+    if data.is_null() || uas_data.is_null() {
+        return OdidStatus::NullPointer;
+    }
+
+    let input = slice::from_raw_parts(data, length);
+
+    match decode(input, &mut *uas_data) {
+        Ok(()) => OdidStatus::Ok,
+        Err(error) => OdidStatus::from(error),
+    }
+}
+
+/// Clears every member of `uas_data`.
+#[no_mangle]
+pub unsafe extern "C" fn odid_uas_data_init(uas_data: *mut OdidUasData) {
+    if !uas_data.is_null() {
+        // all zero is a valid OdidUasData: no flags set, empty strings
+        uas_data.write_bytes(0, 1);
+    }
+}
+
+/// Decodes a raw 25 byte message, or a message pack, header included.
+#[no_mangle]
+pub unsafe extern "C" fn odid_decode_message(
+    data: *const u8,
+    length: usize,
+    uas_data: *mut OdidUasData,
+) -> OdidStatus {
+    decode_with(data, length, uas_data, |input, uas_data| {
+        uas_data.decode(input)
+    })
+}
+
+/// Decodes an 802.11 Beacon or NAN action frame, starting at the frame control field (no
+/// radiotap header, no FCS required).
+#[no_mangle]
+pub unsafe extern "C" fn odid_decode_wifi_frame(
+    data: *const u8,
+    length: usize,
+    uas_data: *mut OdidUasData,
+) -> OdidStatus {
+    decode_with(data, length, uas_data, |input, uas_data| {
+        uas_data.decode(wifi_frame_message_pack(input)?)
+    })
+}
+
+/// Decodes the AD structures of a Bluetooth advertisement, as reported by the controller.
+#[no_mangle]
+pub unsafe extern "C" fn odid_decode_bluetooth_frame(
+    data: *const u8,
+    length: usize,
+    uas_data: *mut OdidUasData,
+) -> OdidStatus {
+    decode_with(data, length, uas_data, |input, uas_data| {
+        let (_, frame) = parse_bluetooth_advertising_data(input)?;
+
+        uas_data.decode(frame.message)
+    })
+}
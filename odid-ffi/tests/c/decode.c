@@ -0,0 +1,197 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "odid.h"
+
+#define MAX_FIXTURE_SIZE 512
+
+static int failures = 0;
+
+#define CHECK(condition)                                                \
+  do {                                                                  \
+    if (!(condition)) {                                                 \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+              #condition);                                              \
+      failures++;                                                       \
+    }                                                                   \
+  } while (0)
+
+/* reads a fixture in the "[1, 2, 3]" format, returns the number of bytes */
+static size_t read_fixture(const char *fixtures, const char *name, uint8_t *bytes) {
+  char path[1024];
+  size_t length = 0;
+  unsigned value = 0;
+  int in_number = 0;
+  int c;
+
+  snprintf(path, sizeof(path), "%s/%s", fixtures, name);
+
+  FILE *file = fopen(path, "r");
+  if (file == NULL) {
+    fprintf(stderr, "could not open %s\n", path);
+    failures++;
+    return 0;
+  }
+
+  while ((c = fgetc(file)) != EOF) {
+    if (c >= '0' && c <= '9') {
+      value = value * 10 + (unsigned)(c - '0');
+      in_number = 1;
+    } else if (in_number) {
+      if (length < MAX_FIXTURE_SIZE) {
+        bytes[length++] = (uint8_t)value;
+      }
+      value = 0;
+      in_number = 0;
+    }
+  }
+
+  fclose(file);
+  return length;
+}
+
+/* the Wi-Fi fixtures are radiotap captures, the header length is at bytes 2 and 3 */
+static size_t radiotap_length(const uint8_t *bytes) {
+  return (size_t)bytes[2] | ((size_t)bytes[3] << 8);
+}
+
+static void test_decode_messages(const char *fixtures) {
+  uint8_t bytes[MAX_FIXTURE_SIZE];
+  OdidUasData uas_data;
+  size_t length;
+
+  odid_uas_data_init(&uas_data);
+
+  length = read_fixture(fixtures, "odid_basic_id_packet.txt", bytes);
+  CHECK(odid_decode_message(bytes, length, &uas_data) == ODID_STATUS_OK);
+  CHECK(uas_data.has_basic_id);
+  CHECK(uas_data.basic_id.uas_id_type == 1);
+  CHECK(strcmp(uas_data.basic_id.uas_id, "1787F04BM24010011039") == 0);
+  CHECK(!uas_data.has_location);
+
+  length = read_fixture(fixtures, "odid_location_packet.txt", bytes);
+  CHECK(odid_decode_message(bytes, length, &uas_data) == ODID_STATUS_OK);
+  CHECK(uas_data.has_location);
+  CHECK(uas_data.location.latitude_int == 358025790);
+  CHECK(uas_data.location.longitude_int == -907109691);
+  CHECK(uas_data.location.timestamp == 9701);
+
+  length = read_fixture(fixtures, "odid_system_packet.txt", bytes);
+  CHECK(odid_decode_message(bytes, length, &uas_data) == ODID_STATUS_OK);
+  CHECK(uas_data.has_system_message);
+  CHECK(uas_data.system_message.operator_altitude_geodetic == 2134);
+  CHECK(uas_data.system_message.timestamp == 173376968);
+
+  length = read_fixture(fixtures, "odid_self_id_packet.txt", bytes);
+  CHECK(odid_decode_message(bytes, length, &uas_data) == ODID_STATUS_OK);
+  CHECK(uas_data.has_self_id);
+  CHECK(strcmp(uas_data.self_id.description, "Jojo Test") == 0);
+
+  /* the basic id is kept while other messages arrive */
+  CHECK(uas_data.has_basic_id);
+  CHECK(!uas_data.has_operator_id);
+}
+
+static void test_decode_message_pack(const char *fixtures) {
+  uint8_t bytes[MAX_FIXTURE_SIZE];
+  OdidUasData uas_data;
+  size_t length;
+
+  odid_uas_data_init(&uas_data);
+
+  length = read_fixture(fixtures, "odid_message_pack.txt", bytes);
+  CHECK(odid_decode_message(bytes, length, &uas_data) == ODID_STATUS_OK);
+  CHECK(uas_data.has_basic_id);
+  CHECK(uas_data.has_system_message);
+  CHECK(uas_data.has_location);
+  CHECK(uas_data.has_self_id);
+  CHECK(uas_data.location.latitude_int == 358025796);
+}
+
+static void test_decode_wifi_frames(const char *fixtures) {
+  uint8_t bytes[MAX_FIXTURE_SIZE];
+  OdidUasData uas_data;
+  size_t length;
+  size_t header_length;
+
+  odid_uas_data_init(&uas_data);
+
+  length = read_fixture(fixtures, "wlan_beacon_packet_data.txt", bytes);
+  header_length = radiotap_length(bytes);
+  CHECK(odid_decode_wifi_frame(bytes + header_length, length - header_length, &uas_data) ==
+        ODID_STATUS_OK);
+  CHECK(uas_data.has_location);
+  CHECK(uas_data.location.latitude_int == 358025796);
+  CHECK(uas_data.location.longitude_int == -907110086);
+
+  length = read_fixture(fixtures, "wifi_packet_data.txt", bytes);
+  header_length = radiotap_length(bytes);
+  CHECK(odid_decode_wifi_frame(bytes + header_length, length - header_length, &uas_data) ==
+        ODID_STATUS_OK);
+  CHECK(uas_data.location.latitude_int == 358026271);
+  CHECK(uas_data.location.longitude_int == -907113683);
+
+  /* a data frame carries no Open Drone ID */
+  bytes[header_length] = 0x08;
+  CHECK(odid_decode_wifi_frame(bytes + header_length, length - header_length, &uas_data) ==
+        ODID_STATUS_UNEXPECTED_TYPE);
+}
+
+static void test_decode_bluetooth_frame(const char *fixtures) {
+  uint8_t service_data[MAX_FIXTURE_SIZE];
+  uint8_t frame[MAX_FIXTURE_SIZE];
+  OdidUasData uas_data;
+  size_t length;
+
+  odid_uas_data_init(&uas_data);
+
+  length = read_fixture(fixtures, "bluetooth_location_packet.txt", service_data);
+
+  /* flags, then the Remote ID service data with UUID 0xFFFA */
+  frame[0] = 0x02;
+  frame[1] = 0x01;
+  frame[2] = 0x06;
+  frame[3] = (uint8_t)(3 + length);
+  frame[4] = 0x16;
+  frame[5] = 0xfa;
+  frame[6] = 0xff;
+  memcpy(frame + 7, service_data, length);
+
+  CHECK(odid_decode_bluetooth_frame(frame, 7 + length, &uas_data) == ODID_STATUS_OK);
+  CHECK(uas_data.has_location);
+  CHECK(uas_data.location.latitude_int == 358025790);
+}
+
+static void test_decode_errors(void) {
+  uint8_t bytes[10] = {0x01};
+  OdidUasData uas_data;
+
+  odid_uas_data_init(&uas_data);
+
+  CHECK(odid_decode_message(bytes, sizeof(bytes), &uas_data) == ODID_STATUS_TOO_SHORT);
+  CHECK(odid_decode_message(NULL, 25, &uas_data) == ODID_STATUS_NULL_POINTER);
+  CHECK(odid_decode_message(bytes, sizeof(bytes), NULL) == ODID_STATUS_NULL_POINTER);
+  CHECK(odid_decode_wifi_frame(bytes, 0, &uas_data) == ODID_STATUS_UNEXPECTED_TYPE);
+  CHECK(odid_decode_bluetooth_frame(bytes, 0, &uas_data) == ODID_STATUS_UNEXPECTED_TYPE);
+  CHECK(!uas_data.has_location);
+}
+
+int main(int argc, char **argv) {
+  if (argc != 2) {
+    fprintf(stderr, "usage: %s <fixtures directory>\n", argv[0]);
+    return 2;
+  }
+
+  test_decode_messages(argv[1]);
+  test_decode_message_pack(argv[1]);
+  test_decode_wifi_frames(argv[1]);
+  test_decode_bluetooth_frame(argv[1]);
+  test_decode_errors();
+
+  if (failures > 0) {
+    fprintf(stderr, "%d checks failed\n", failures);
+    return 1;
+  }
+
+  return 0;
+}
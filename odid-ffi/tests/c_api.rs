@@ -0,0 +1,40 @@
+//! Builds tests/c/decode.c against include/odid.h and the static library, then runs it on
+//! the shared fixtures.
+#![cfg(unix)]
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// the static library is built next to the test executable
+fn static_library() -> PathBuf {
+    let test_executable = env::current_exe().unwrap();
+
+    test_executable.parent().unwrap().join("libodid_ffi.a")
+}
+
+#[test]
+fn test_c_api() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let executable = Path::new(env!("CARGO_TARGET_TMPDIR")).join("odid_c_api_test");
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let status = Command::new(compiler)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-I"])
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c/decode.c"))
+        .arg(static_library())
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&executable)
+        .status()
+        .expect("Failed to run the C compiler");
+
+    assert!(status.success(), "C test program failed to build");
+
+    let status = Command::new(&executable)
+        .arg(manifest_dir.join("../fixtures"))
+        .status()
+        .expect("Failed to run the C test program");
+
+    assert!(status.success(), "C test program failed");
+}
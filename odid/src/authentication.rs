@@ -0,0 +1,235 @@
+use alloc::vec::Vec;
+
+use arrayvec::ArrayVec;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AuthType, Authentication, AuthenticationBlob, AUTH_MAX_PAGES, AUTH_PAGE_0_DATA_LENGTH,
+    AUTH_PAGE_N_DATA_LENGTH,
+};
+
+/// Pages of a single authentication sequence are broadcast back to back; anything older
+/// than this belongs to a previous sequence.
+pub const AUTHENTICATION_TIMEOUT_SECONDS: i64 = 10;
+
+/// Collects Authentication pages for one drone until every page of the sequence is present.
+/// Pages may arrive in any order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthenticationAssembler {
+    auth_type: Option<AuthType>,
+    last_page_index: Option<u8>,
+    length: Option<u8>,
+    timestamp: Option<u32>,
+    pages: [Option<ArrayVec<u8, AUTH_PAGE_N_DATA_LENGTH>>; AUTH_MAX_PAGES],
+    started: Option<DateTime<Utc>>,
+}
+
+impl AuthenticationAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.iter().all(|page| page.is_none())
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        match self.started {
+            Some(started) => {
+                now.signed_duration_since(started).num_seconds() > AUTHENTICATION_TIMEOUT_SECONDS
+            }
+            None => false,
+        }
+    }
+
+    /// Page indexes still outstanding. Until page 0 has been seen the total page count is
+    /// unknown, so page 0 is the only page reported as missing.
+    pub fn missing_pages(&self) -> ArrayVec<u8, AUTH_MAX_PAGES> {
+        let last_page_index = match self.last_page_index {
+            Some(last_page_index) => last_page_index,
+            None => return [0].into_iter().collect(),
+        };
+
+        // `push` keeps the last page index within the pages
+        (0..=last_page_index)
+            .filter(|page| self.pages[*page as usize].is_none())
+            .collect()
+    }
+
+    /// Adds a page to the sequence, returning the reassembled authentication data once the
+    /// final missing page arrives.
+    pub fn push(
+        &mut self,
+        page: Authentication,
+        received: DateTime<Utc>,
+    ) -> Option<AuthenticationBlob> {
+        let beyond_pages = |index: u8| index as usize >= AUTH_MAX_PAGES;
+
+        if beyond_pages(page.page) || page.last_page_index.is_some_and(beyond_pages) {
+            return None;
+        }
+
+        if self.is_expired(received) {
+            self.reset();
+        }
+
+        // a different auth type or a new page 0 timestamp means a new sequence started
+        let auth_type_changed =
+            matches!(&self.auth_type, Some(auth_type) if *auth_type != page.auth_type);
+        let timestamp_changed = matches!(
+            (self.timestamp, page.timestamp),
+            (Some(current), Some(new)) if current != new
+        );
+
+        if auth_type_changed || timestamp_changed {
+            self.reset();
+        }
+
+        if self.started.is_none() {
+            self.started = Some(received);
+        }
+
+        if page.page == 0 {
+            self.last_page_index = page.last_page_index;
+            self.length = page.length;
+            self.timestamp = page.timestamp;
+        }
+
+        self.auth_type = Some(page.auth_type);
+        self.pages[page.page as usize] = Some(page.auth_data);
+
+        if !self.missing_pages().is_empty() {
+            return None;
+        }
+
+        let blob = self.assemble();
+        self.reset();
+        blob
+    }
+
+    fn assemble(&self) -> Option<AuthenticationBlob> {
+        let last_page_index = self.last_page_index? as usize;
+        let length = self.length? as usize;
+
+        // reject a length that the announced pages cannot carry
+        if length > AUTH_PAGE_0_DATA_LENGTH + last_page_index * AUTH_PAGE_N_DATA_LENGTH {
+            return None;
+        }
+
+        let mut auth_data: Vec<u8> = self.pages[..=last_page_index]
+            .iter()
+            .flatten()
+            .flatten()
+            .cloned()
+            .collect();
+
+        auth_data.truncate(length);
+
+        Some(AuthenticationBlob {
+            auth_type: self.auth_type.clone()?,
+            timestamp: self.timestamp?,
+            auth_data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn page_0(last_page_index: u8, length: u8, timestamp: u32) -> Authentication {
+        Authentication {
+            auth_type: AuthType::MessageSetSignature,
+            page: 0,
+            last_page_index: Some(last_page_index),
+            length: Some(length),
+            timestamp: Some(timestamp),
+            auth_data: [0xa0; AUTH_PAGE_0_DATA_LENGTH].into_iter().collect(),
+        }
+    }
+
+    fn page_n(page: u8) -> Authentication {
+        Authentication {
+            auth_type: AuthType::MessageSetSignature,
+            page,
+            last_page_index: None,
+            length: None,
+            timestamp: None,
+            auth_data: ArrayVec::from([0xa0 + page; AUTH_PAGE_N_DATA_LENGTH]),
+        }
+    }
+
+    #[test]
+    fn test_assemble_out_of_order_pages() {
+        let now = Utc::now();
+        let mut assembler = AuthenticationAssembler::new();
+
+        assert_eq!(assembler.push(page_n(2), now), None);
+        assert_eq!(assembler.missing_pages().as_slice(), [0]);
+        assert_eq!(assembler.push(page_0(2, 50, 1234), now), None);
+        assert_eq!(assembler.missing_pages().as_slice(), [1]);
+
+        let blob = assembler.push(page_n(1), now).unwrap();
+
+        assert_eq!(blob.auth_type, AuthType::MessageSetSignature);
+        assert_eq!(blob.timestamp, 1234);
+        assert_eq!(blob.auth_data.len(), 50);
+        assert_eq!(blob.auth_data[AUTH_PAGE_0_DATA_LENGTH], 0xa1);
+        assert_eq!(blob.auth_data[49], 0xa2);
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn test_assemble_times_out_stale_pages() {
+        let now = Utc::now();
+        let mut assembler = AuthenticationAssembler::new();
+
+        assert_eq!(assembler.push(page_0(1, 30, 1234), now), None);
+
+        let later = now + Duration::seconds(AUTHENTICATION_TIMEOUT_SECONDS + 1);
+
+        assert!(assembler.is_expired(later));
+        assert_eq!(assembler.push(page_n(1), later), None);
+        assert_eq!(assembler.missing_pages().as_slice(), [0]);
+    }
+
+    #[test]
+    fn test_assemble_restarts_on_new_sequence() {
+        let now = Utc::now();
+        let mut assembler = AuthenticationAssembler::new();
+
+        assert_eq!(assembler.push(page_0(2, 50, 1234), now), None);
+        assert_eq!(assembler.push(page_n(1), now), None);
+        assert_eq!(assembler.push(page_0(2, 50, 5678), now), None);
+        assert_eq!(assembler.missing_pages().as_slice(), [1, 2]);
+    }
+
+    #[test]
+    fn test_assemble_rejects_oversized_length() {
+        let now = Utc::now();
+        let mut assembler = AuthenticationAssembler::new();
+
+        assert_eq!(assembler.push(page_0(0, 200, 1234), now), None);
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn test_assemble_ignores_pages_beyond_capacity() {
+        let now = Utc::now();
+        let mut assembler = AuthenticationAssembler::new();
+
+        assert_eq!(assembler.push(page_n(AUTH_MAX_PAGES as u8), now), None);
+        assert_eq!(
+            assembler.push(page_0(AUTH_MAX_PAGES as u8, 50, 1234), now),
+            None
+        );
+        assert!(assembler.is_empty());
+        assert_eq!(assembler.missing_pages().as_slice(), [0]);
+    }
+}
@@ -0,0 +1,178 @@
+//! Bluetooth Legacy (4.x) and Long Range (5.x) advertisements carrying Open Drone ID in
+//! the ASTM Remote ID service data.
+
+use nom::bytes::complete::take;
+use nom::number::complete::le_u8;
+
+use super::{parse_with_layer, ParseLayer, ParseResult};
+
+// Service Data - 16-bit UUID, the AD type Remote ID is broadcast in
+pub const SERVICE_DATA_AD_TYPE: u8 = 0x16;
+pub const ASTM_REMOTE_ID_SERVICE_UUID: u16 = 0xFFFA;
+
+#[derive(Debug)]
+pub struct BluetoothAdvertisementFrame<'a> {
+    pub app_code: u8,
+    pub counter: u8,
+    // a single 25 byte message, or a message pack on Bluetooth 5 Long Range
+    pub message: &'a [u8],
+}
+
+pub fn parse_bluetooth_advertisement_frame(
+    input: &[u8],
+) -> ParseResult<'_, BluetoothAdvertisementFrame<'_>> {
+    parse_with_layer(ParseLayer::BluetoothAdvertisement, input, |input| {
+        let (input, app_code) = le_u8(input)?;
+        let (input, counter) = le_u8(input)?;
+        let (input, message) = take(input.len())(input)?;
+
+        Ok((
+            input,
+            BluetoothAdvertisementFrame {
+                app_code,
+                counter,
+                message,
+            },
+        ))
+    })
+}
+
+/// Finds the Remote ID service data among the AD structures of an advertising payload, as
+/// reported by the controller, and parses it from the app code onwards.
+pub fn parse_bluetooth_advertising_data(
+    input: &[u8],
+) -> ParseResult<'_, BluetoothAdvertisementFrame<'_>> {
+    parse_with_layer(ParseLayer::BluetoothAdvertisement, input, |input| {
+        let mut ad_structures = input;
+
+        while !ad_structures.is_empty() {
+            let (rest, ad_length) = le_u8(ad_structures)?;
+
+            // zero padding ends the significant part of the payload
+            if ad_length == 0 {
+                break;
+            }
+
+            let (rest, ad_structure) = take(ad_length)(rest)?;
+            let (ad_data, ad_type) = le_u8(ad_structure)?;
+
+            ad_structures = rest;
+
+            if ad_type != SERVICE_DATA_AD_TYPE || ad_data.len() < 2 {
+                continue;
+            }
+
+            if u16::from_le_bytes([ad_data[0], ad_data[1]]) != ASTM_REMOTE_ID_SERVICE_UUID {
+                continue;
+            }
+
+            let (message, app_code) = le_u8(&ad_data[2..])?;
+            let (message, counter) = le_u8(message)?;
+
+            return Ok((
+                rest,
+                BluetoothAdvertisementFrame {
+                    app_code,
+                    counter,
+                    message,
+                },
+            ));
+        }
+
+        Err(nom::Err::Error(nom::error::Error::new(
+            ad_structures,
+            nom::error::ErrorKind::Tag,
+        )))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_location, parse_message, parse_message_pack, Location, OdidMessage};
+
+    use super::*;
+    use crate::test_util::read_fixture;
+
+    #[test]
+    fn test_parse_bluetooth_advertisement_frame() {
+        let input = read_fixture("../fixtures/bluetooth_location_packet.txt").unwrap();
+
+        let bt_advertisement_frame: Option<BluetoothAdvertisementFrame> =
+            match parse_bluetooth_advertisement_frame(&input) {
+                Ok((_, frame)) => Some(frame),
+                Err(e) => {
+                    eprintln!("Failed to parse Bluetooth advertisement frame: {:?}", e);
+                    None
+                }
+            };
+
+        assert!(bt_advertisement_frame.is_some());
+
+        let bt_advertisement_frame = bt_advertisement_frame.unwrap();
+
+        assert_eq!(bt_advertisement_frame.app_code, 0x0d);
+        assert_eq!(bt_advertisement_frame.counter, 33);
+        assert_eq!(bt_advertisement_frame.message.len(), 25);
+
+        // skip the message type and version header
+        let location: Option<Location> = match parse_location(&bt_advertisement_frame.message[1..])
+        {
+            Ok((_, location)) => Some(location),
+            Err(_) => None,
+        };
+
+        assert!(location.is_some());
+
+        let location = location.unwrap();
+
+        assert_eq!(location.latitude_int, 358025790);
+        assert_eq!(location.longitude_int, -907109691);
+    }
+
+    #[test]
+    fn test_parse_bluetooth_messages() {
+        let input = read_fixture("../fixtures/bluetooth_basic_id_packet.txt").unwrap();
+
+        let (_, bt_advertisement_frame) = parse_bluetooth_advertisement_frame(&input).unwrap();
+
+        match parse_message(bt_advertisement_frame.message) {
+            Ok((_, OdidMessage::BasicId(basic_id))) => {
+                assert_eq!(basic_id.uas_id.as_str(), "1787F04BM24010011039");
+            }
+            result => panic!("Expected basic id, got {:?}", result),
+        }
+
+        // Bluetooth 5 Long Range carries a message pack after the app code and counter
+        let mut input = vec![0x0d, 0x01];
+        input.extend(read_fixture("../fixtures/odid_message_pack.txt").unwrap());
+
+        let (_, bt_advertisement_frame) = parse_bluetooth_advertisement_frame(&input).unwrap();
+        let (_, message_pack) = parse_message_pack(bt_advertisement_frame.message).unwrap();
+
+        assert_eq!(message_pack.messages.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_bluetooth_advertising_data() {
+        let service_data = read_fixture("../fixtures/bluetooth_location_packet.txt").unwrap();
+
+        // flags, then the Remote ID service data
+        let mut input = vec![0x02, 0x01, 0x06];
+        input.push(3 + service_data.len() as u8);
+        input.extend([SERVICE_DATA_AD_TYPE, 0xfa, 0xff]);
+        input.extend(&service_data);
+
+        let (_, bt_advertisement_frame) = parse_bluetooth_advertising_data(&input).unwrap();
+
+        assert_eq!(bt_advertisement_frame.app_code, 0x0d);
+        assert_eq!(bt_advertisement_frame.message, &service_data[2..]);
+
+        assert_eq!(
+            parse_bluetooth_advertising_data(&input[..3])
+                .unwrap_err()
+                .reason,
+            crate::ParseErrorReason::UnexpectedType
+        );
+        assert!(parse_bluetooth_advertising_data(&input[..10]).is_err());
+    }
+}
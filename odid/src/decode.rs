@@ -0,0 +1,514 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ClassificationType, Location, OperatorLocationType, SystemMessage, UaCategory, UaClass,
+};
+
+// wire encodings of the Location message, see ASTM F3411 table "Location/Vector Message"
+const LAT_LON_MULTIPLIER: f64 = 1e-7;
+const ALTITUDE_MULTIPLIER: f64 = 0.5;
+const ALTITUDE_OFFSET: f64 = -1000.0;
+const SPEED_MULTIPLIER_LOW: f64 = 0.25;
+const SPEED_MULTIPLIER_HIGH: f64 = 0.75;
+const VERTICAL_SPEED_MULTIPLIER: f64 = 0.5;
+const TIMESTAMP_MULTIPLIER: f64 = 0.1;
+const AREA_RADIUS_MULTIPLIER: f64 = 10.0;
+
+// System message timestamps count from 2019-01-01 00:00:00 UTC
+const UNIX_2019_OFFSET: i64 = 1546300800;
+
+// sentinel values the spec reserves for "unknown"; a track of 361 degrees is unknown too
+const SPEED_UNKNOWN: u8 = 255;
+const VERTICAL_SPEED_UNKNOWN: i8 = 126;
+const ALTITUDE_UNKNOWN: u16 = 0;
+const TIMESTAMP_UNKNOWN: u16 = 0xFFFF;
+const TIMESTAMP_MAX: u16 = 36000;
+const SECONDS_PER_HOUR: i64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum OperationalStatus {
+    Undeclared,
+    Ground,
+    Airborne,
+    Emergency,
+    RemoteIdSystemFailure,
+    Other(u8),
+}
+
+impl From<u8> for OperationalStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => OperationalStatus::Undeclared,
+            1 => OperationalStatus::Ground,
+            2 => OperationalStatus::Airborne,
+            3 => OperationalStatus::Emergency,
+            4 => OperationalStatus::RemoteIdSystemFailure,
+            _ => OperationalStatus::Other(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HeightReference {
+    AboveTakeoff,
+    AboveGroundLevel,
+}
+
+impl From<u8> for HeightReference {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => HeightReference::AboveTakeoff,
+            _ => HeightReference::AboveGroundLevel,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HorizontalAccuracy {
+    Unknown,
+    Nm10,
+    Nm4,
+    Nm2,
+    Nm1,
+    Nm05,
+    Nm03,
+    Nm01,
+    Nm005,
+    Meters30,
+    Meters10,
+    Meters3,
+    Meters1,
+    Other(u8),
+}
+
+impl From<u8> for HorizontalAccuracy {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => HorizontalAccuracy::Unknown,
+            1 => HorizontalAccuracy::Nm10,
+            2 => HorizontalAccuracy::Nm4,
+            3 => HorizontalAccuracy::Nm2,
+            4 => HorizontalAccuracy::Nm1,
+            5 => HorizontalAccuracy::Nm05,
+            6 => HorizontalAccuracy::Nm03,
+            7 => HorizontalAccuracy::Nm01,
+            8 => HorizontalAccuracy::Nm005,
+            9 => HorizontalAccuracy::Meters30,
+            10 => HorizontalAccuracy::Meters10,
+            11 => HorizontalAccuracy::Meters3,
+            12 => HorizontalAccuracy::Meters1,
+            _ => HorizontalAccuracy::Other(value),
+        }
+    }
+}
+
+impl HorizontalAccuracy {
+    /// Upper bound of the 95% accuracy bucket in metres.
+    pub fn meters(&self) -> Option<f64> {
+        match self {
+            HorizontalAccuracy::Nm10 => Some(18520.0),
+            HorizontalAccuracy::Nm4 => Some(7408.0),
+            HorizontalAccuracy::Nm2 => Some(3704.0),
+            HorizontalAccuracy::Nm1 => Some(1852.0),
+            HorizontalAccuracy::Nm05 => Some(926.0),
+            HorizontalAccuracy::Nm03 => Some(555.6),
+            HorizontalAccuracy::Nm01 => Some(185.2),
+            HorizontalAccuracy::Nm005 => Some(92.6),
+            HorizontalAccuracy::Meters30 => Some(30.0),
+            HorizontalAccuracy::Meters10 => Some(10.0),
+            HorizontalAccuracy::Meters3 => Some(3.0),
+            HorizontalAccuracy::Meters1 => Some(1.0),
+            HorizontalAccuracy::Unknown | HorizontalAccuracy::Other(_) => None,
+        }
+    }
+}
+
+/// Shared by the geodetic and the barometric altitude accuracy fields.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum VerticalAccuracy {
+    Unknown,
+    Meters150,
+    Meters45,
+    Meters25,
+    Meters10,
+    Meters3,
+    Meters1,
+    Other(u8),
+}
+
+impl From<u8> for VerticalAccuracy {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => VerticalAccuracy::Unknown,
+            1 => VerticalAccuracy::Meters150,
+            2 => VerticalAccuracy::Meters45,
+            3 => VerticalAccuracy::Meters25,
+            4 => VerticalAccuracy::Meters10,
+            5 => VerticalAccuracy::Meters3,
+            6 => VerticalAccuracy::Meters1,
+            _ => VerticalAccuracy::Other(value),
+        }
+    }
+}
+
+impl VerticalAccuracy {
+    /// Upper bound of the 95% accuracy bucket in metres.
+    pub fn meters(&self) -> Option<f64> {
+        match self {
+            VerticalAccuracy::Meters150 => Some(150.0),
+            VerticalAccuracy::Meters45 => Some(45.0),
+            VerticalAccuracy::Meters25 => Some(25.0),
+            VerticalAccuracy::Meters10 => Some(10.0),
+            VerticalAccuracy::Meters3 => Some(3.0),
+            VerticalAccuracy::Meters1 => Some(1.0),
+            VerticalAccuracy::Unknown | VerticalAccuracy::Other(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SpeedAccuracy {
+    Unknown,
+    MetersPerSecond10,
+    MetersPerSecond3,
+    MetersPerSecond1,
+    MetersPerSecond03,
+    Other(u8),
+}
+
+impl From<u8> for SpeedAccuracy {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SpeedAccuracy::Unknown,
+            1 => SpeedAccuracy::MetersPerSecond10,
+            2 => SpeedAccuracy::MetersPerSecond3,
+            3 => SpeedAccuracy::MetersPerSecond1,
+            4 => SpeedAccuracy::MetersPerSecond03,
+            _ => SpeedAccuracy::Other(value),
+        }
+    }
+}
+
+impl SpeedAccuracy {
+    /// Upper bound of the 95% accuracy bucket in metres per second.
+    pub fn meters_per_second(&self) -> Option<f64> {
+        match self {
+            SpeedAccuracy::MetersPerSecond10 => Some(10.0),
+            SpeedAccuracy::MetersPerSecond3 => Some(3.0),
+            SpeedAccuracy::MetersPerSecond1 => Some(1.0),
+            SpeedAccuracy::MetersPerSecond03 => Some(0.3),
+            SpeedAccuracy::Unknown | SpeedAccuracy::Other(_) => None,
+        }
+    }
+}
+
+/// A Location message converted to physical units. Fields the transmitter reports as
+/// unknown are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DecodedLocation {
+    pub status: OperationalStatus,
+    pub height_reference: HeightReference,
+    /// Degrees clockwise from true north.
+    pub track_direction: Option<f64>,
+    /// Horizontal ground speed in m/s.
+    pub speed: Option<f64>,
+    /// Vertical speed in m/s, positive up.
+    pub vertical_speed: Option<f64>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Barometric pressure altitude in metres.
+    pub altitude_pressure: Option<f64>,
+    /// WGS-84 HAE altitude in metres.
+    pub altitude_geodetic: Option<f64>,
+    /// Height in metres relative to `height_reference`.
+    pub height: Option<f64>,
+    pub horizontal_accuracy: HorizontalAccuracy,
+    pub vertical_accuracy: VerticalAccuracy,
+    pub barometric_altitude_accuracy: VerticalAccuracy,
+    pub speed_accuracy: SpeedAccuracy,
+    /// Seconds since the top of the current UTC hour.
+    pub timestamp: Option<f64>,
+    /// Timestamp accuracy in seconds.
+    pub timestamp_accuracy: Option<f64>,
+}
+
+fn decode_altitude(value: u16) -> Option<f64> {
+    if value == ALTITUDE_UNKNOWN {
+        return None;
+    }
+
+    Some(value as f64 * ALTITUDE_MULTIPLIER + ALTITUDE_OFFSET)
+}
+
+fn decode_track_direction(direction: u8, ew_direction: u8) -> Option<f64> {
+    let direction = if ew_direction == 1 {
+        direction as u16 + 180
+    } else {
+        direction as u16
+    };
+
+    if direction >= 360 {
+        return None;
+    }
+
+    Some(direction as f64)
+}
+
+fn decode_speed(speed: u8, speed_multiplier: u8) -> Option<f64> {
+    if speed_multiplier == 0 {
+        return Some(speed as f64 * SPEED_MULTIPLIER_LOW);
+    }
+
+    if speed == SPEED_UNKNOWN {
+        return None;
+    }
+
+    Some(speed as f64 * SPEED_MULTIPLIER_HIGH + u8::MAX as f64 * SPEED_MULTIPLIER_LOW)
+}
+
+fn decode_vertical_speed(vertical_speed: i8) -> Option<f64> {
+    if vertical_speed == VERTICAL_SPEED_UNKNOWN {
+        return None;
+    }
+
+    Some(vertical_speed as f64 * VERTICAL_SPEED_MULTIPLIER)
+}
+
+impl From<&Location> for DecodedLocation {
+    fn from(location: &Location) -> Self {
+        let timestamp = if location.timestamp == TIMESTAMP_UNKNOWN {
+            None
+        } else {
+            Some(location.timestamp as f64 * TIMESTAMP_MULTIPLIER)
+        };
+
+        let timestamp_accuracy = if location.timestamp_accuracy == 0 {
+            None
+        } else {
+            Some(location.timestamp_accuracy as f64 * TIMESTAMP_MULTIPLIER)
+        };
+
+        DecodedLocation {
+            status: OperationalStatus::from(location.status),
+            height_reference: HeightReference::from(location.height_type),
+            track_direction: decode_track_direction(
+                location.tracking_direction,
+                location.ew_direction,
+            ),
+            speed: decode_speed(location.speed, location.speed_multiplier),
+            vertical_speed: decode_vertical_speed(location.vertical_speed),
+            latitude: location.latitude_int as f64 * LAT_LON_MULTIPLIER,
+            longitude: location.longitude_int as f64 * LAT_LON_MULTIPLIER,
+            altitude_pressure: decode_altitude(location.altitude_pressure),
+            altitude_geodetic: decode_altitude(location.altitude_geodetic),
+            height: decode_altitude(location.height),
+            horizontal_accuracy: HorizontalAccuracy::from(location.horizontal_accuracy),
+            vertical_accuracy: VerticalAccuracy::from(location.vertical_accuracy),
+            barometric_altitude_accuracy: VerticalAccuracy::from(
+                location.barometric_altitude_accuracy,
+            ),
+            speed_accuracy: SpeedAccuracy::from(location.speed_accuracy),
+            timestamp,
+            timestamp_accuracy,
+        }
+    }
+}
+
+/// A System message converted to physical units.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DecodedSystemMessage {
+    pub operator_location_type: OperatorLocationType,
+    pub classification_type: ClassificationType,
+    pub operator_latitude: f64,
+    pub operator_longitude: f64,
+    pub area_count: u16,
+    /// Radius of the operating area in metres.
+    pub area_radius: f64,
+    /// Operating area ceiling and floor, WGS-84 HAE in metres.
+    pub area_ceiling: Option<f64>,
+    pub area_floor: Option<f64>,
+    pub ua_category: UaCategory,
+    pub ua_class: UaClass,
+    /// Operator WGS-84 HAE altitude in metres.
+    pub operator_altitude_geodetic: Option<f64>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Converts a System message timestamp (seconds since 2019-01-01 00:00 UTC) to UTC.
+pub fn decode_system_timestamp(timestamp: u32) -> Option<DateTime<Utc>> {
+    if timestamp == 0 {
+        return None;
+    }
+
+    DateTime::from_timestamp(UNIX_2019_OFFSET + timestamp as i64, 0)
+}
+
+/// Places a Location timestamp (tenths of seconds past the UTC hour) in the hour closest to
+/// `received`, so a message sent just before the top of the hour and received just after it
+/// lands in the previous hour.
+pub fn decode_location_timestamp(timestamp: u16, received: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if timestamp == TIMESTAMP_UNKNOWN || timestamp > TIMESTAMP_MAX {
+        return None;
+    }
+
+    let received_seconds = received.timestamp();
+    let hour_start = DateTime::from_timestamp(
+        received_seconds - received_seconds.rem_euclid(SECONDS_PER_HOUR),
+        0,
+    )?;
+    let transmitted = hour_start + Duration::milliseconds(timestamp as i64 * 100);
+
+    let half_hour = Duration::seconds(SECONDS_PER_HOUR / 2);
+    let offset = transmitted - received;
+
+    if offset > half_hour {
+        Some(transmitted - Duration::seconds(SECONDS_PER_HOUR))
+    } else if offset < -half_hour {
+        Some(transmitted + Duration::seconds(SECONDS_PER_HOUR))
+    } else {
+        Some(transmitted)
+    }
+}
+
+impl From<&SystemMessage> for DecodedSystemMessage {
+    fn from(system_message: &SystemMessage) -> Self {
+        DecodedSystemMessage {
+            operator_location_type: system_message.operator_location_type.clone(),
+            classification_type: system_message.classification_type.clone(),
+            operator_latitude: system_message.operator_latitude_int as f64 * LAT_LON_MULTIPLIER,
+            operator_longitude: system_message.operator_longitude_int as f64 * LAT_LON_MULTIPLIER,
+            area_count: system_message.area_count,
+            area_radius: system_message.area_radius as f64 * AREA_RADIUS_MULTIPLIER,
+            area_ceiling: decode_altitude(system_message.area_ceiling),
+            area_floor: decode_altitude(system_message.area_floor),
+            ua_category: system_message.ua_category.clone(),
+            ua_class: system_message.ua_class.clone(),
+            operator_altitude_geodetic: decode_altitude(system_message.operator_altitude_geodetic),
+            timestamp: decode_system_timestamp(system_message.timestamp),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_fixture;
+    use crate::{parse_message, OdidMessage};
+
+    fn location_fixture() -> Location {
+        let bytes = read_fixture("../fixtures/odid_location_packet.txt").unwrap();
+
+        match parse_message(&bytes) {
+            Ok((_, OdidMessage::Location(location))) => location,
+            result => panic!("Expected location, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_decode_location_fixture() {
+        let decoded = DecodedLocation::from(&location_fixture());
+
+        assert_eq!(decoded.status, OperationalStatus::Ground);
+        assert_eq!(decoded.height_reference, HeightReference::AboveTakeoff);
+        assert_eq!(decoded.track_direction, Some(0.0));
+        assert_eq!(decoded.speed, Some(0.0));
+        assert_eq!(decoded.vertical_speed, Some(0.0));
+        assert!((decoded.latitude - 35.802579).abs() < 1e-6);
+        assert!((decoded.longitude - -90.7109691).abs() < 1e-6);
+        assert_eq!(decoded.altitude_pressure, None);
+        assert_eq!(decoded.altitude_geodetic, Some(68.0));
+        assert_eq!(decoded.height, Some(1.0));
+        assert_eq!(decoded.horizontal_accuracy.meters(), Some(3.0));
+        assert_eq!(decoded.vertical_accuracy.meters(), Some(10.0));
+        assert_eq!(
+            decoded.barometric_altitude_accuracy,
+            VerticalAccuracy::Unknown
+        );
+        assert_eq!(decoded.speed_accuracy.meters_per_second(), Some(3.0));
+        assert_eq!(decoded.timestamp, Some(970.1));
+        assert_eq!(decoded.timestamp_accuracy, Some(0.1));
+    }
+
+    #[test]
+    fn test_decode_track_direction() {
+        assert_eq!(decode_track_direction(90, 0), Some(90.0));
+        assert_eq!(decode_track_direction(10, 1), Some(190.0));
+        assert_eq!(decode_track_direction(179, 1), Some(359.0));
+        // 361 is "unknown", anything else above 359 is invalid
+        assert_eq!(decode_track_direction(181, 1), None);
+        assert_eq!(decode_track_direction(180, 1), None);
+    }
+
+    #[test]
+    fn test_decode_speed() {
+        assert_eq!(decode_speed(100, 0), Some(25.0));
+        assert_eq!(decode_speed(100, 1), Some(138.75));
+        assert_eq!(decode_speed(0, 1), Some(63.75));
+        assert_eq!(decode_speed(255, 1), None);
+        assert_eq!(decode_vertical_speed(-10), Some(-5.0));
+        assert_eq!(decode_vertical_speed(126), None);
+    }
+
+    #[test]
+    fn test_decode_system_message_fixture() {
+        let bytes = read_fixture("../fixtures/odid_system_packet.txt").unwrap();
+
+        let system_message = match parse_message(&bytes) {
+            Ok((_, OdidMessage::SystemMessage(system_message))) => system_message,
+            result => panic!("Expected system message, got {:?}", result),
+        };
+
+        let decoded = DecodedSystemMessage::from(&system_message);
+
+        assert!((decoded.operator_latitude - 35.8025741).abs() < 1e-6);
+        assert!((decoded.operator_longitude - -90.7109656).abs() < 1e-6);
+        assert_eq!(decoded.area_radius, 0.0);
+        assert_eq!(decoded.area_ceiling, None);
+        assert_eq!(decoded.operator_altitude_geodetic, Some(67.0));
+        assert_eq!(
+            decoded.timestamp,
+            DateTime::parse_from_rfc3339("2024-06-29T16:16:08Z")
+                .ok()
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+        );
+    }
+
+    #[test]
+    fn test_decode_location_timestamp() {
+        let received = DateTime::parse_from_rfc3339("2024-06-29T16:16:10Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // 16:16:08.1
+        assert_eq!(
+            decode_location_timestamp(9681, received),
+            Some(received - Duration::milliseconds(1900))
+        );
+        assert_eq!(decode_location_timestamp(TIMESTAMP_UNKNOWN, received), None);
+        assert_eq!(decode_location_timestamp(36001, received), None);
+    }
+
+    #[test]
+    fn test_decode_location_timestamp_hour_rollover() {
+        let received = DateTime::parse_from_rfc3339("2024-06-29T17:00:01Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // sent at 16:59:59.5, received after the top of the hour
+        assert_eq!(
+            decode_location_timestamp(35995, received),
+            Some(received - Duration::milliseconds(1500))
+        );
+
+        let received = DateTime::parse_from_rfc3339("2024-06-29T16:59:59Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // transmitter clock already past the top of the hour
+        assert_eq!(
+            decode_location_timestamp(5, received),
+            Some(received + Duration::milliseconds(1500))
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use alloc::vec::Vec;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::repo::decode_hex;
+use super::{
+    Authentication, BasicId, Location, MessagePack, OdidMessage, Operator, ProtocolVersion,
+    RemoteIdMessage, SelfId, SystemMessage, UasIdType, AUTH_PAGE_0_DATA_LENGTH,
+    AUTH_PAGE_N_DATA_LENGTH, ODID_MESSAGE_SIZE, OPERATOR_ID_LENGTH, SELF_ID_DESCRIPTION_LENGTH,
+    UAS_ID_LENGTH, UTM_ID_LENGTH,
+};
+
+fn new_message(message_type: RemoteIdMessage, version: ProtocolVersion) -> [u8; ODID_MESSAGE_SIZE] {
+    let mut message = [0u8; ODID_MESSAGE_SIZE];
+    message[0] = (u8::from(message_type) << 4) | (u8::from(version) & 0x0F);
+    message
+}
+
+// copies at most `output.len()` bytes, leaving the rest null padded
+fn write_padded(output: &mut [u8], input: &[u8]) {
+    let length = input.len().min(output.len());
+    output[..length].copy_from_slice(&input[..length]);
+}
+
+/// `None` when a binary UAS ID isn't the hex of a full ID, or a Specific Session ID is encoded
+/// for a version that reserved its ID type.
+pub fn encode_basic_id(
+    basic_id: &BasicId,
+    version: ProtocolVersion,
+) -> Option<[u8; ODID_MESSAGE_SIZE]> {
+    let session_ids_reserved = matches!(
+        version,
+        ProtocolVersion::F3411v19 | ProtocolVersion::F3411v20
+    );
+    let mut message = new_message(RemoteIdMessage::BasicId, version);
+
+    message[1] =
+        (u8::from(basic_id.uas_id_type.clone()) << 4) | (u8::from(basic_id.ua_type.clone()) & 0x0F);
+
+    match basic_id.uas_id_type {
+        UasIdType::SpecificSessionId if session_ids_reserved => return None,
+        UasIdType::SpecificSessionId => {
+            message[2] = basic_id
+                .session_id_type
+                .clone()
+                .map(u8::from)
+                .unwrap_or_default();
+            message[3..2 + UAS_ID_LENGTH]
+                .copy_from_slice(&decode_full_hex::<{ UAS_ID_LENGTH - 1 }>(&basic_id.uas_id)?);
+        }
+        UasIdType::UtmId => {
+            message[2..2 + UTM_ID_LENGTH]
+                .copy_from_slice(&decode_full_hex::<UTM_ID_LENGTH>(&basic_id.uas_id)?)
+        }
+        _ => write_padded(
+            &mut message[2..2 + UAS_ID_LENGTH],
+            basic_id.uas_id.as_bytes(),
+        ),
+    }
+
+    Some(message)
+}
+
+// binary IDs are written whole, a shorter hex string is as malformed as a bad digit
+fn decode_full_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    decode_hex::<N>(hex)?.into_inner().ok()
+}
+
+pub fn encode_location(location: &Location, version: ProtocolVersion) -> [u8; ODID_MESSAGE_SIZE] {
+    let mut message = new_message(RemoteIdMessage::Location, version);
+
+    message[1] = (location.status << 4)
+        | ((location.height_type & 0x01) << 2)
+        | ((location.ew_direction & 0x01) << 1)
+        | (location.speed_multiplier & 0x01);
+    message[2] = location.tracking_direction;
+    message[3] = location.speed;
+    message[4] = location.vertical_speed as u8;
+    LittleEndian::write_i32(&mut message[5..9], location.latitude_int);
+    LittleEndian::write_i32(&mut message[9..13], location.longitude_int);
+    LittleEndian::write_u16(&mut message[13..15], location.altitude_pressure);
+    LittleEndian::write_u16(&mut message[15..17], location.altitude_geodetic);
+    LittleEndian::write_u16(&mut message[17..19], location.height);
+    message[19] = (location.vertical_accuracy << 4) | (location.horizontal_accuracy & 0x0F);
+    message[20] = (location.barometric_altitude_accuracy << 4) | (location.speed_accuracy & 0x0F);
+    LittleEndian::write_u16(&mut message[21..23], location.timestamp);
+    message[23] = location.timestamp_accuracy & 0x0F;
+
+    message
+}
+
+pub fn encode_authentication(
+    authentication: &Authentication,
+    version: ProtocolVersion,
+) -> [u8; ODID_MESSAGE_SIZE] {
+    let mut message = new_message(RemoteIdMessage::Authentication, version);
+
+    message[1] = (u8::from(authentication.auth_type.clone()) << 4) | (authentication.page & 0x0F);
+
+    if authentication.page > 0 {
+        write_padded(
+            &mut message[2..2 + AUTH_PAGE_N_DATA_LENGTH],
+            &authentication.auth_data,
+        );
+
+        return message;
+    }
+
+    message[2] = authentication.last_page_index.unwrap_or_default();
+    message[3] = authentication.length.unwrap_or_default();
+    LittleEndian::write_u32(
+        &mut message[4..8],
+        authentication.timestamp.unwrap_or_default(),
+    );
+    write_padded(
+        &mut message[8..8 + AUTH_PAGE_0_DATA_LENGTH],
+        &authentication.auth_data,
+    );
+
+    message
+}
+
+pub fn encode_self_id(self_id: &SelfId, version: ProtocolVersion) -> [u8; ODID_MESSAGE_SIZE] {
+    let mut message = new_message(RemoteIdMessage::SelfId, version);
+
+    message[1] = u8::from(self_id.description_type.clone());
+    write_padded(
+        &mut message[2..2 + SELF_ID_DESCRIPTION_LENGTH],
+        self_id.description.as_bytes(),
+    );
+
+    message
+}
+
+/// F3411-19 has no classification, operator altitude or timestamp, they are left out for v0
+/// the way the parser leaves them undeclared.
+pub fn encode_system_message(
+    system_message: &SystemMessage,
+    version: ProtocolVersion,
+) -> [u8; ODID_MESSAGE_SIZE] {
+    let v0 = version == ProtocolVersion::F3411v19;
+    let mut message = new_message(RemoteIdMessage::SystemMessage, version);
+
+    message[1] = u8::from(system_message.operator_location_type.clone()) & 0x03;
+    LittleEndian::write_i32(&mut message[2..6], system_message.operator_latitude_int);
+    LittleEndian::write_i32(&mut message[6..10], system_message.operator_longitude_int);
+    LittleEndian::write_u16(&mut message[10..12], system_message.area_count);
+    message[12] = system_message.area_radius;
+    LittleEndian::write_u16(&mut message[13..15], system_message.area_ceiling);
+    LittleEndian::write_u16(&mut message[15..17], system_message.area_floor);
+
+    if v0 {
+        return message;
+    }
+
+    message[1] |= (u8::from(system_message.classification_type.clone()) & 0x07) << 2;
+    message[17] = (u8::from(system_message.ua_category.clone()) << 4)
+        | (u8::from(system_message.ua_class.clone()) & 0x0F);
+    LittleEndian::write_u16(
+        &mut message[18..20],
+        system_message.operator_altitude_geodetic,
+    );
+    LittleEndian::write_u32(&mut message[20..24], system_message.timestamp);
+
+    message
+}
+
+pub fn encode_operator_id(
+    operator: &Operator,
+    version: ProtocolVersion,
+) -> [u8; ODID_MESSAGE_SIZE] {
+    let mut message = new_message(RemoteIdMessage::OperatorId, version);
+
+    message[1] = operator.operator_id_type;
+    write_padded(
+        &mut message[2..2 + OPERATOR_ID_LENGTH],
+        operator.operator_id.as_bytes(),
+    );
+
+    message
+}
+
+/// Encodes a single message with `version` in its header, laid out as that version defines
+/// it. Unknown messages have no body to encode, see `encode_basic_id` for the other `None`s.
+pub fn encode_message(
+    message: &OdidMessage,
+    version: ProtocolVersion,
+) -> Option<[u8; ODID_MESSAGE_SIZE]> {
+    match message {
+        OdidMessage::BasicId(basic_id) => encode_basic_id(basic_id, version),
+        OdidMessage::Location(location) => Some(encode_location(location, version)),
+        OdidMessage::Authentication(authentication) => {
+            Some(encode_authentication(authentication, version))
+        }
+        OdidMessage::SelfId(self_id) => Some(encode_self_id(self_id, version)),
+        OdidMessage::SystemMessage(system_message) => {
+            Some(encode_system_message(system_message, version))
+        }
+        OdidMessage::OperatorId(operator) => Some(encode_operator_id(operator, version)),
+        OdidMessage::Unknown(_) => None,
+    }
+}
+
+/// Encodes a Message Pack, its messages in the pack's version. `single_msg_size` and
+/// `num_messages` are derived from the messages; `None` is returned when a message can't be
+/// encoded.
+pub fn encode_message_pack(message_pack: &MessagePack) -> Option<Vec<u8>> {
+    let mut pack = Vec::with_capacity(3 + message_pack.messages.len() * ODID_MESSAGE_SIZE);
+
+    pack.push((u8::from(RemoteIdMessage::MessagePack) << 4) | (message_pack.version & 0x0F));
+    pack.push(ODID_MESSAGE_SIZE as u8);
+    pack.push(message_pack.messages.len() as u8);
+
+    for message in &message_pack.messages {
+        pack.extend_from_slice(&encode_message(
+            message,
+            ProtocolVersion::from(message_pack.version),
+        )?);
+    }
+
+    Some(pack)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_fixture;
+    use crate::{parse_message, parse_message_pack, AuthType, SessionIdType, UaType};
+    use arrayvec::{ArrayString, ArrayVec};
+
+    fn round_trip(file_path: &str) {
+        let bytes = read_fixture(file_path).unwrap();
+
+        let (_, message) = parse_message(&bytes).unwrap();
+        let version = ProtocolVersion::from(bytes[0] & 0x0F);
+
+        assert_eq!(encode_message(&message, version).unwrap().to_vec(), bytes);
+    }
+
+    #[test]
+    fn test_round_trip_basic_id() {
+        round_trip("../fixtures/odid_basic_id_packet.txt");
+    }
+
+    #[test]
+    fn test_round_trip_location() {
+        round_trip("../fixtures/odid_location_packet.txt");
+    }
+
+    #[test]
+    fn test_round_trip_system_message() {
+        round_trip("../fixtures/odid_system_packet.txt");
+    }
+
+    #[test]
+    fn test_round_trip_self_id() {
+        round_trip("../fixtures/odid_self_id_packet.txt");
+    }
+
+    #[test]
+    fn test_round_trip_operator_id() {
+        let operator = Operator {
+            operator_id_type: 0,
+            operator_id: ArrayString::from("FIN87astrdge12k8").unwrap(),
+        };
+
+        let bytes = encode_operator_id(&operator, ProtocolVersion::F3411v22a);
+
+        assert_eq!(bytes[0], 0x52);
+
+        match parse_message(&bytes) {
+            Ok((_, OdidMessage::OperatorId(decoded))) => {
+                assert_eq!(decoded.operator_id_type, operator.operator_id_type);
+                assert_eq!(decoded.operator_id, operator.operator_id);
+            }
+            result => panic!("Expected operator id, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_specific_session_id() {
+        let basic_id = BasicId {
+            uas_id_type: UasIdType::SpecificSessionId,
+            ua_type: UaType::HelicopterOrDrone,
+            uas_id: ArrayString::from("00ff10203040506070809000a0b0c0d0e0f001").unwrap(),
+            session_id_type: Some(SessionIdType::IetfDrip),
+        };
+
+        let bytes = encode_basic_id(&basic_id, ProtocolVersion::F3411v22a).unwrap();
+
+        assert_eq!(bytes[1], 0x42);
+        assert_eq!(bytes[2], 0x01);
+        assert_eq!(bytes[3..5], [0x00, 0xff]);
+
+        match parse_message(&bytes) {
+            Ok((_, OdidMessage::BasicId(decoded))) => {
+                assert_eq!(decoded.uas_id_type, basic_id.uas_id_type);
+                assert_eq!(decoded.session_id_type, basic_id.session_id_type);
+                assert_eq!(decoded.uas_id, basic_id.uas_id);
+            }
+            result => panic!("Expected basic id, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_utm_id() {
+        // nulls and bytes outside ASCII survive the round trip
+        let basic_id = BasicId {
+            uas_id_type: UasIdType::UtmId,
+            ua_type: UaType::HelicopterOrDrone,
+            uas_id: ArrayString::from("12003456789a4bcd80ef00ff0011fe80").unwrap(),
+            session_id_type: None,
+        };
+
+        let bytes = encode_basic_id(&basic_id, ProtocolVersion::F3411v22a).unwrap();
+
+        assert_eq!(bytes[1], 0x32);
+        assert_eq!(bytes[2..5], [0x12, 0x00, 0x34]);
+        assert_eq!(bytes[10..12], [0x80, 0xef]);
+        assert_eq!(bytes[18..22], [0x00; 4]);
+
+        match parse_message(&bytes) {
+            Ok((_, OdidMessage::BasicId(decoded))) => {
+                assert_eq!(decoded.uas_id_type, basic_id.uas_id_type);
+                assert_eq!(decoded.session_id_type, None);
+                assert_eq!(decoded.uas_id, basic_id.uas_id);
+            }
+            result => panic!("Expected basic id, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_encode_basic_id_rejects_malformed_ids() {
+        let mut basic_id = BasicId {
+            uas_id_type: UasIdType::SpecificSessionId,
+            ua_type: UaType::HelicopterOrDrone,
+            uas_id: ArrayString::from("00ff10203040506070809000a0b0c0d0e0f0zz").unwrap(),
+            session_id_type: Some(SessionIdType::IetfDrip),
+        };
+
+        assert!(encode_basic_id(&basic_id, ProtocolVersion::F3411v22a).is_none());
+
+        basic_id.uas_id = ArrayString::from("00ff1020").unwrap();
+        assert!(encode_basic_id(&basic_id, ProtocolVersion::F3411v22a).is_none());
+
+        // session IDs were reserved before F3411-22a
+        basic_id.uas_id = ArrayString::from("00ff10203040506070809000a0b0c0d0e0f001").unwrap();
+        assert!(encode_basic_id(&basic_id, ProtocolVersion::F3411v22a).is_some());
+        assert!(encode_basic_id(&basic_id, ProtocolVersion::F3411v20).is_none());
+
+        basic_id.uas_id_type = UasIdType::UtmId;
+        assert!(encode_basic_id(&basic_id, ProtocolVersion::F3411v22a).is_none());
+    }
+
+    #[test]
+    fn test_round_trip_older_versions() {
+        let mut bytes = read_fixture("../fixtures/odid_location_packet.txt").unwrap();
+        bytes[0] = (bytes[0] & 0xF0) | u8::from(ProtocolVersion::F3411v20);
+
+        let (_, message) = parse_message(&bytes).unwrap();
+        assert_eq!(
+            encode_message(&message, ProtocolVersion::F3411v20)
+                .unwrap()
+                .to_vec(),
+            bytes
+        );
+
+        // F3411-19 System messages end after the area floor
+        let mut bytes = read_fixture("../fixtures/odid_system_packet.txt").unwrap();
+        bytes[0] &= 0xF0;
+        bytes[1] &= 0x03;
+        bytes[17..25].copy_from_slice(&[0; 8]);
+
+        let (_, message) = parse_message(&bytes).unwrap();
+        assert_eq!(
+            encode_message(&message, ProtocolVersion::F3411v19)
+                .unwrap()
+                .to_vec(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn test_round_trip_authentication() {
+        let page = Authentication {
+            auth_type: AuthType::UasIdSignature,
+            page: 0,
+            last_page_index: Some(1),
+            length: Some(40),
+            timestamp: Some(123456),
+            auth_data: ArrayVec::try_from(&[0x11; AUTH_PAGE_0_DATA_LENGTH][..]).unwrap(),
+        };
+
+        let bytes = encode_authentication(&page, ProtocolVersion::F3411v22a);
+
+        match parse_message(&bytes) {
+            Ok((_, OdidMessage::Authentication(decoded))) => {
+                assert_eq!(decoded.auth_type, page.auth_type);
+                assert_eq!(decoded.last_page_index, page.last_page_index);
+                assert_eq!(decoded.length, page.length);
+                assert_eq!(decoded.timestamp, page.timestamp);
+                assert_eq!(decoded.auth_data, page.auth_data);
+            }
+            result => panic!("Expected authentication, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_message_pack() {
+        let bytes = read_fixture("../fixtures/odid_message_pack.txt").unwrap();
+
+        let (_, message_pack) = parse_message_pack(&bytes).unwrap();
+        let encoded = encode_message_pack(&message_pack).unwrap();
+
+        // the transmitter fills the reserved bytes of its basic id message with 0xa5, the
+        // encoder always writes them as zero
+        let mut expected = bytes.clone();
+        expected[25..28].copy_from_slice(&[0, 0, 0]);
+
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn test_encode_message_pack_rejects_unknown_messages() {
+        let bytes = read_fixture("../fixtures/odid_system_packet.txt").unwrap();
+        let (_, message) = parse_message(&bytes).unwrap();
+
+        let message_pack = MessagePack {
+            version: u8::from(ProtocolVersion::F3411v22a),
+            single_msg_size: ODID_MESSAGE_SIZE as u8,
+            num_messages: 2,
+            messages: ArrayVec::from_iter([message, OdidMessage::Unknown(0xe)]),
+        };
+
+        assert!(encode_message_pack(&message_pack).is_none());
+    }
+}
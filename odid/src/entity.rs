@@ -0,0 +1,520 @@
+use alloc::vec::Vec;
+
+use arrayvec::{ArrayString, ArrayVec};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AUTH_PAGE_N_DATA_LENGTH, ODID_PACK_MAX_MESSAGES, OPERATOR_ID_LENGTH,
+    SELF_ID_DESCRIPTION_LENGTH, UAS_ID_TEXT_LENGTH,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UasIdType {
+    None,
+    SerialNumber,
+    CaaRegistration,
+    UtmId,
+    SpecificSessionId,
+    Other(u8),
+}
+
+impl From<u8> for UasIdType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => UasIdType::None,
+            1 => UasIdType::SerialNumber,
+            2 => UasIdType::CaaRegistration,
+            3 => UasIdType::UtmId,
+            4 => UasIdType::SpecificSessionId,
+            _ => UasIdType::Other(value),
+        }
+    }
+}
+
+impl From<UasIdType> for u8 {
+    fn from(value: UasIdType) -> Self {
+        match value {
+            UasIdType::None => 0,
+            UasIdType::SerialNumber => 1,
+            UasIdType::CaaRegistration => 2,
+            UasIdType::UtmId => 3,
+            UasIdType::SpecificSessionId => 4,
+            UasIdType::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UaType {
+    Undeclared,
+    Aeroplane,
+    HelicopterOrDrone,
+    Gyroplane,
+    HybridLift,
+    Ornithopter,
+    Glider,
+    Kite,
+    FreeBalloon,
+    CaptiveBalloon,
+    Airship,
+    FreeFallParachute,
+    Rocket,
+    TetheredAircraft,
+    GroundObstacle,
+    Other(u8),
+}
+
+impl From<u8> for UaType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => UaType::Undeclared,
+            1 => UaType::Aeroplane,
+            2 => UaType::HelicopterOrDrone,
+            3 => UaType::Gyroplane,
+            4 => UaType::HybridLift,
+            5 => UaType::Ornithopter,
+            6 => UaType::Glider,
+            7 => UaType::Kite,
+            8 => UaType::FreeBalloon,
+            9 => UaType::CaptiveBalloon,
+            10 => UaType::Airship,
+            11 => UaType::FreeFallParachute,
+            12 => UaType::Rocket,
+            13 => UaType::TetheredAircraft,
+            14 => UaType::GroundObstacle,
+            _ => UaType::Other(value),
+        }
+    }
+}
+
+impl From<UaType> for u8 {
+    fn from(value: UaType) -> Self {
+        match value {
+            UaType::Undeclared => 0,
+            UaType::Aeroplane => 1,
+            UaType::HelicopterOrDrone => 2,
+            UaType::Gyroplane => 3,
+            UaType::HybridLift => 4,
+            UaType::Ornithopter => 5,
+            UaType::Glider => 6,
+            UaType::Kite => 7,
+            UaType::FreeBalloon => 8,
+            UaType::CaptiveBalloon => 9,
+            UaType::Airship => 10,
+            UaType::FreeFallParachute => 11,
+            UaType::Rocket => 12,
+            UaType::TetheredAircraft => 13,
+            UaType::GroundObstacle => 14,
+            UaType::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasicId {
+    pub uas_id_type: UasIdType,
+    pub ua_type: UaType,
+    /// ASCII for serial numbers and CAA registrations. UTM IDs and Specific Session IDs are
+    /// binary and kept as lowercase hex, of the 16 byte UUID and of the 19 bytes following
+    /// the session ID type respectively.
+    pub uas_id: ArrayString<UAS_ID_TEXT_LENGTH>,
+    pub session_id_type: Option<SessionIdType>,
+}
+
+/// Session ID type, the first byte of a Specific Session ID.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SessionIdType {
+    IetfDrip,
+    Ieee16092HashedId8,
+    Other(u8),
+}
+
+impl From<u8> for SessionIdType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => SessionIdType::IetfDrip,
+            2 => SessionIdType::Ieee16092HashedId8,
+            _ => SessionIdType::Other(value),
+        }
+    }
+}
+
+impl From<SessionIdType> for u8 {
+    fn from(value: SessionIdType) -> Self {
+        match value {
+            SessionIdType::IetfDrip => 1,
+            SessionIdType::Ieee16092HashedId8 => 2,
+            SessionIdType::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub status: u8,
+    pub ew_direction: u8,
+    pub height_type: u8,
+    pub tracking_direction: u8,
+    pub speed_multiplier: u8,
+    pub speed: u8,
+    pub vertical_speed: i8,
+    pub latitude_int: i32,
+    pub longitude_int: i32,
+    pub altitude_pressure: u16,
+    pub altitude_geodetic: u16,
+    pub height: u16,
+    pub horizontal_accuracy: u8,
+    pub vertical_accuracy: u8,
+    pub barometric_altitude_accuracy: u8,
+    pub speed_accuracy: u8,
+    pub timestamp: u16,
+    pub timestamp_accuracy: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AuthType {
+    None,
+    UasIdSignature,
+    OperatorIdSignature,
+    MessageSetSignature,
+    NetworkRemoteId,
+    SpecificMethod,
+    Other(u8),
+}
+
+impl From<u8> for AuthType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => AuthType::None,
+            1 => AuthType::UasIdSignature,
+            2 => AuthType::OperatorIdSignature,
+            3 => AuthType::MessageSetSignature,
+            4 => AuthType::NetworkRemoteId,
+            5 => AuthType::SpecificMethod,
+            _ => AuthType::Other(value),
+        }
+    }
+}
+
+impl From<AuthType> for u8 {
+    fn from(value: AuthType) -> Self {
+        match value {
+            AuthType::None => 0,
+            AuthType::UasIdSignature => 1,
+            AuthType::OperatorIdSignature => 2,
+            AuthType::MessageSetSignature => 3,
+            AuthType::NetworkRemoteId => 4,
+            AuthType::SpecificMethod => 5,
+            AuthType::Other(value) => value,
+        }
+    }
+}
+
+/// A single Authentication page. Only page 0 carries the last page index, the total
+/// length of the authentication data and the timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Authentication {
+    pub auth_type: AuthType,
+    pub page: u8,
+    pub last_page_index: Option<u8>,
+    pub length: Option<u8>,
+    pub timestamp: Option<u32>,
+    pub auth_data: ArrayVec<u8, AUTH_PAGE_N_DATA_LENGTH>,
+}
+
+/// Authentication data reassembled from all of its pages.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthenticationBlob {
+    pub auth_type: AuthType,
+    pub timestamp: u32,
+    pub auth_data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum OperatorLocationType {
+    TakeOff,
+    LiveGNSS,
+    FixedLocation,
+    Other(u8),
+}
+
+impl From<u8> for OperatorLocationType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => OperatorLocationType::TakeOff,
+            1 => OperatorLocationType::LiveGNSS,
+            2 => OperatorLocationType::FixedLocation,
+            _ => OperatorLocationType::Other(value),
+        }
+    }
+}
+
+impl From<OperatorLocationType> for u8 {
+    fn from(value: OperatorLocationType) -> Self {
+        match value {
+            OperatorLocationType::TakeOff => 0,
+            OperatorLocationType::LiveGNSS => 1,
+            OperatorLocationType::FixedLocation => 2,
+            OperatorLocationType::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ClassificationType {
+    Undeclared,
+    EuropeanUnion,
+    Other(u8),
+}
+
+impl From<u8> for ClassificationType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ClassificationType::Undeclared,
+            1 => ClassificationType::EuropeanUnion,
+            _ => ClassificationType::Other(value),
+        }
+    }
+}
+
+impl From<ClassificationType> for u8 {
+    fn from(value: ClassificationType) -> Self {
+        match value {
+            ClassificationType::Undeclared => 0,
+            ClassificationType::EuropeanUnion => 1,
+            ClassificationType::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UaCategory {
+    Undeclared,
+    Open,
+    Specific,
+    Certified,
+    Other(u8),
+}
+
+impl From<u8> for UaCategory {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => UaCategory::Undeclared,
+            1 => UaCategory::Open,
+            2 => UaCategory::Specific,
+            3 => UaCategory::Certified,
+            _ => UaCategory::Other(value),
+        }
+    }
+}
+
+impl From<UaCategory> for u8 {
+    fn from(value: UaCategory) -> Self {
+        match value {
+            UaCategory::Undeclared => 0,
+            UaCategory::Open => 1,
+            UaCategory::Specific => 2,
+            UaCategory::Certified => 3,
+            UaCategory::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UaClass {
+    Undeclared,
+    Class0,
+    Class1,
+    Class2,
+    Class3,
+    Class4,
+    Class5,
+    Class6,
+    Other(u8),
+}
+
+impl From<u8> for UaClass {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => UaClass::Undeclared,
+            1 => UaClass::Class0,
+            2 => UaClass::Class1,
+            3 => UaClass::Class2,
+            4 => UaClass::Class3,
+            5 => UaClass::Class4,
+            6 => UaClass::Class5,
+            7 => UaClass::Class6,
+            _ => UaClass::Other(value),
+        }
+    }
+}
+
+impl From<UaClass> for u8 {
+    fn from(value: UaClass) -> Self {
+        match value {
+            UaClass::Undeclared => 0,
+            UaClass::Class0 => 1,
+            UaClass::Class1 => 2,
+            UaClass::Class2 => 3,
+            UaClass::Class3 => 4,
+            UaClass::Class4 => 5,
+            UaClass::Class5 => 6,
+            UaClass::Class6 => 7,
+            UaClass::Other(value) => value,
+        }
+    }
+}
+
+/// System message as defined by F3411-20 (protocol version 1) and F3411-22a (version 2).
+/// Category and class are only meaningful when `classification_type` is `EuropeanUnion`;
+/// the timestamp is seconds since 2019-01-01 00:00 UTC and zero when not transmitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemMessage {
+    pub operator_location_type: OperatorLocationType,
+    pub classification_type: ClassificationType,
+    pub operator_latitude_int: i32,
+    pub operator_longitude_int: i32,
+    pub area_count: u16,
+    pub area_radius: u8,
+    pub area_ceiling: u16,
+    pub area_floor: u16,
+    pub ua_category: UaCategory,
+    pub ua_class: UaClass,
+    pub operator_altitude_geodetic: u16,
+    pub timestamp: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operator {
+    pub operator_id_type: u8,
+    pub operator_id: ArrayString<OPERATOR_ID_LENGTH>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SelfIdDescriptionType {
+    Text,
+    Emergency,
+    ExtendedStatus,
+    Other(u8),
+}
+
+impl From<u8> for SelfIdDescriptionType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SelfIdDescriptionType::Text,
+            1 => SelfIdDescriptionType::Emergency,
+            2 => SelfIdDescriptionType::ExtendedStatus,
+            _ => SelfIdDescriptionType::Other(value),
+        }
+    }
+}
+
+impl From<SelfIdDescriptionType> for u8 {
+    fn from(value: SelfIdDescriptionType) -> Self {
+        match value {
+            SelfIdDescriptionType::Text => 0,
+            SelfIdDescriptionType::Emergency => 1,
+            SelfIdDescriptionType::ExtendedStatus => 2,
+            SelfIdDescriptionType::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfId {
+    pub description_type: SelfIdDescriptionType,
+    pub description: ArrayString<SELF_ID_DESCRIPTION_LENGTH>,
+}
+
+/// Protocol version from the low nibble of every message header.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ProtocolVersion {
+    F3411v19,
+    F3411v20,
+    F3411v22a,
+    Other(u8),
+}
+
+impl From<u8> for ProtocolVersion {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ProtocolVersion::F3411v19,
+            1 => ProtocolVersion::F3411v20,
+            2 => ProtocolVersion::F3411v22a,
+            _ => ProtocolVersion::Other(value),
+        }
+    }
+}
+
+impl From<ProtocolVersion> for u8 {
+    fn from(value: ProtocolVersion) -> Self {
+        match value {
+            ProtocolVersion::F3411v19 => 0,
+            ProtocolVersion::F3411v20 => 1,
+            ProtocolVersion::F3411v22a => 2,
+            ProtocolVersion::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RemoteIdMessage {
+    BasicId,
+    Location,
+    Authentication,
+    SelfId,
+    SystemMessage,
+    OperatorId,
+    MessagePack,
+    Unknown,
+}
+
+impl From<RemoteIdMessage> for u8 {
+    fn from(value: RemoteIdMessage) -> Self {
+        match value {
+            RemoteIdMessage::BasicId => 0,
+            RemoteIdMessage::Location => 1,
+            RemoteIdMessage::Authentication => 2,
+            RemoteIdMessage::SelfId => 3,
+            RemoteIdMessage::SystemMessage => 4,
+            RemoteIdMessage::OperatorId => 5,
+            RemoteIdMessage::MessagePack => 0xf,
+            RemoteIdMessage::Unknown => 0xe,
+        }
+    }
+}
+
+impl From<u8> for RemoteIdMessage {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => RemoteIdMessage::BasicId,
+            1 => RemoteIdMessage::Location,
+            2 => RemoteIdMessage::Authentication,
+            3 => RemoteIdMessage::SelfId,
+            4 => RemoteIdMessage::SystemMessage,
+            5 => RemoteIdMessage::OperatorId,
+            0xf => RemoteIdMessage::MessagePack,
+            _ => RemoteIdMessage::Unknown,
+        }
+    }
+}
+
+/// A decoded ODID message, independent of the transport it was received over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OdidMessage {
+    BasicId(BasicId),
+    Location(Location),
+    Authentication(Authentication),
+    SelfId(SelfId),
+    SystemMessage(SystemMessage),
+    OperatorId(Operator),
+    Unknown(u8),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagePack {
+    pub version: u8,
+    pub single_msg_size: u8,
+    pub num_messages: u8,
+    pub messages: ArrayVec<OdidMessage, ODID_PACK_MAX_MESSAGES>,
+}
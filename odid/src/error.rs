@@ -0,0 +1,112 @@
+use core::fmt;
+
+use nom::error::ErrorKind;
+use nom::IResult;
+use serde::{Deserialize, Serialize};
+
+/// The layer of the capture stack a parser works on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ParseLayer {
+    Radiotap,
+    Ppi,
+    Ieee80211,
+    NanServiceDescriptor,
+    BeaconVendorIe,
+    BluetoothAdvertisement,
+    MessagePack,
+    OdidMessage,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ParseErrorReason {
+    /// The input ended before the field did.
+    TooShort,
+    /// A type, tag or OUI didn't match what the parser handles.
+    UnexpectedType,
+    /// A length or count field disagrees with the data.
+    InvalidLength,
+    /// A field holds a value the format doesn't allow.
+    InvalidValue,
+}
+
+impl From<ErrorKind> for ParseErrorReason {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::Eof | ErrorKind::Complete => ParseErrorReason::TooShort,
+            ErrorKind::Tag => ParseErrorReason::UnexpectedType,
+            ErrorKind::LengthValue => ParseErrorReason::InvalidLength,
+            _ => ParseErrorReason::InvalidValue,
+        }
+    }
+}
+
+/// A parse failure, with the byte offset into the input handed to the failing layer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ParseError {
+    pub layer: ParseLayer,
+    pub offset: usize,
+    pub reason: ParseErrorReason,
+}
+
+pub type ParseResult<'a, T> = Result<(&'a [u8], T), ParseError>;
+
+impl ParseError {
+    pub fn new(layer: ParseLayer, offset: usize, reason: ParseErrorReason) -> Self {
+        ParseError {
+            layer,
+            offset,
+            reason,
+        }
+    }
+
+    /// Converts a nom error raised while parsing `input`.
+    pub fn from_nom(
+        layer: ParseLayer,
+        input: &[u8],
+        error: nom::Err<nom::error::Error<&[u8]>>,
+    ) -> Self {
+        match error {
+            nom::Err::Incomplete(_) => {
+                ParseError::new(layer, input.len(), ParseErrorReason::TooShort)
+            }
+            nom::Err::Error(error) | nom::Err::Failure(error) => ParseError::new(
+                layer,
+                input.len() - error.input.len(),
+                ParseErrorReason::from(error.code),
+            ),
+        }
+    }
+
+    /// Moves the offset past `by` bytes the caller consumed before handing over its input.
+    pub fn shifted(self, by: usize) -> Self {
+        ParseError {
+            offset: self.offset + by,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} parse error at offset {}: {:?}",
+            self.layer, self.offset, self.reason
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Runs a nom parser, reporting any failure against `layer`.
+pub fn parse_with_layer<'a, T, F>(
+    layer: ParseLayer,
+    input: &'a [u8],
+    parser: F,
+) -> ParseResult<'a, T>
+where
+    F: FnOnce(&'a [u8]) -> IResult<&'a [u8], T>,
+{
+    parser(input).map_err(|error| ParseError::from_nom(layer, input, error))
+}
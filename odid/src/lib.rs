@@ -0,0 +1,32 @@
+//! ASTM F3411 Open Drone ID messages: parsing, decoding to physical units, validation
+//! and encoding. Builds without `std` (with `alloc`) when the default `std` feature is
+//! turned off.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod authentication;
+mod decode;
+mod encoder;
+mod entity;
+mod error;
+mod operator;
+mod repo;
+mod serial;
+mod validate;
+
+#[cfg(test)]
+mod test_util;
+
+pub mod bluetooth;
+pub mod wifi;
+
+pub use authentication::*;
+pub use decode::*;
+pub use encoder::*;
+pub use entity::*;
+pub use error::*;
+pub use operator::*;
+pub use repo::*;
+pub use serial::*;
+pub use validate::*;
@@ -0,0 +1,147 @@
+use alloc::string::{String, ToString};
+
+use serde::{Deserialize, Serialize};
+
+// EN 4709-002 operator registration numbers are broadcast as a 3 letter country code, a 12
+// character base number and a checksum character. The 3 secret characters that complete
+// the registration number are never broadcast.
+const COUNTRY_CODE_LENGTH: usize = 3;
+const BASE_NUMBER_LENGTH: usize = 12;
+const SECRET_LENGTH: usize = 3;
+const EU_OPERATOR_ID_LENGTH: usize = COUNTRY_CODE_LENGTH + BASE_NUMBER_LENGTH + 1;
+
+const CHECKSUM_ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EuOperatorId {
+    pub country_code: String,
+    pub base_number: String,
+    pub checksum: char,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum OperatorIdStatus {
+    ValidFormat,
+    /// Well formed, but the checksum couldn't be checked without the secret characters.
+    ChecksumUnverified,
+    InvalidChecksum,
+    UnknownScheme,
+}
+
+fn checksum_value(c: char) -> Option<u32> {
+    CHECKSUM_ALPHABET
+        .iter()
+        .position(|b| *b as char == c)
+        .map(|value| value as u32)
+}
+
+/// Splits a broadcast operator ID into its EN 4709-002 parts. `None` means the ID follows
+/// some other scheme.
+pub fn parse_eu_operator_id(operator_id: &str) -> Option<EuOperatorId> {
+    if operator_id.len() != EU_OPERATOR_ID_LENGTH || !operator_id.is_ascii() {
+        return None;
+    }
+
+    let (country_code, rest) = operator_id.split_at(COUNTRY_CODE_LENGTH);
+    let (base_number, checksum) = rest.split_at(BASE_NUMBER_LENGTH);
+
+    if !country_code.chars().all(|c| c.is_ascii_uppercase())
+        || !base_number.chars().all(|c| checksum_value(c).is_some())
+    {
+        return None;
+    }
+
+    Some(EuOperatorId {
+        country_code: country_code.to_string(),
+        base_number: base_number.to_string(),
+        checksum: checksum.chars().next()?,
+    })
+}
+
+/// Luhn mod 36 over the base number followed by the secret characters.
+pub fn eu_operator_id_checksum(base_number: &str, secret: &str) -> Option<char> {
+    let mut factor = 2;
+    let mut sum = 0;
+
+    for c in base_number.chars().chain(secret.chars()).rev() {
+        let addend = factor * checksum_value(c)?;
+
+        factor = if factor == 2 { 1 } else { 2 };
+        sum += addend / 36 + addend % 36;
+    }
+
+    Some(CHECKSUM_ALPHABET[((36 - sum % 36) % 36) as usize] as char)
+}
+
+/// Classifies a broadcast operator ID. The checksum depends on the secret characters, so
+/// without them only its character set can be checked and a well formed ID is
+/// `ChecksumUnverified`.
+pub fn validate_operator_id(operator_id: &str, secret: Option<&str>) -> OperatorIdStatus {
+    let eu_operator_id = match parse_eu_operator_id(operator_id) {
+        Some(eu_operator_id) => eu_operator_id,
+        None => return OperatorIdStatus::UnknownScheme,
+    };
+
+    let checksum = match secret {
+        Some(secret) if secret.len() == SECRET_LENGTH => {
+            eu_operator_id_checksum(&eu_operator_id.base_number, secret)
+        }
+        _ if checksum_value(eu_operator_id.checksum).is_some() => {
+            return OperatorIdStatus::ChecksumUnverified
+        }
+        _ => None,
+    };
+
+    if checksum == Some(eu_operator_id.checksum) {
+        OperatorIdStatus::ValidFormat
+    } else {
+        OperatorIdStatus::InvalidChecksum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_eu_operator_id() {
+        let operator_id = parse_eu_operator_id("FIN87astrdge12k8").unwrap();
+
+        assert_eq!(operator_id.country_code, "FIN");
+        assert_eq!(operator_id.base_number, "87astrdge12k");
+        assert_eq!(operator_id.checksum, '8');
+        assert_eq!(parse_eu_operator_id("FAA-12345"), None);
+    }
+
+    #[test]
+    fn test_validate_operator_id() {
+        assert_eq!(
+            validate_operator_id("FIN87astrdge12k8", Some("xyz")),
+            OperatorIdStatus::ValidFormat
+        );
+        assert_eq!(
+            validate_operator_id("FIN87astrdge12k9", Some("xyz")),
+            OperatorIdStatus::InvalidChecksum
+        );
+        assert_eq!(
+            validate_operator_id("FIN87astrdge12k8", None),
+            OperatorIdStatus::ChecksumUnverified
+        );
+        assert_eq!(
+            validate_operator_id("FIN87astrdge12k9", None),
+            OperatorIdStatus::ChecksumUnverified
+        );
+        assert_eq!(
+            validate_operator_id("FIN87astrdge12k8", Some("xy")),
+            OperatorIdStatus::ChecksumUnverified
+        );
+        assert_eq!(
+            validate_operator_id("FIN87astrdge12k#", None),
+            OperatorIdStatus::InvalidChecksum
+        );
+        assert_eq!(
+            validate_operator_id("N12345", None),
+            OperatorIdStatus::UnknownScheme
+        );
+    }
+}
@@ -0,0 +1,794 @@
+use core::fmt::Write;
+
+use arrayvec::{ArrayString, ArrayVec};
+use nom::bytes::complete::take;
+use nom::number::complete::{le_i32, le_i8, le_u16, le_u32, le_u8};
+
+use super::{
+    parse_with_layer, AuthType, Authentication, BasicId, ClassificationType, Location, MessagePack,
+    OdidMessage, Operator, OperatorLocationType, ParseError, ParseErrorReason, ParseLayer,
+    ParseResult, ProtocolVersion, RemoteIdMessage, SelfId, SelfIdDescriptionType, SessionIdType,
+    SystemMessage, UaCategory, UaClass, UaType, UasIdType,
+};
+
+// every ODID message is a 1 byte type/version header followed by a 24 byte body
+pub const ODID_MESSAGE_SIZE: usize = 25;
+pub const ODID_MESSAGE_BODY_SIZE: usize = 24;
+pub const ODID_PACK_MAX_MESSAGES: usize = 9;
+
+// UAS ID is a fixed 20 byte field, null padded when ASCII
+pub const UAS_ID_LENGTH: usize = 20;
+
+// session IDs are kept as hex of the 19 bytes after the session ID type
+pub const UAS_ID_TEXT_LENGTH: usize = 2 * (UAS_ID_LENGTH - 1);
+
+// UTM assigned IDs are a binary 128 bit UUID at the start of the UAS ID field
+pub const UTM_ID_LENGTH: usize = 16;
+
+// Operator ID is a fixed 20 byte, null padded ASCII field
+pub const OPERATOR_ID_LENGTH: usize = 20;
+
+// Self-ID description is a fixed 23 byte, null padded ASCII field
+pub const SELF_ID_DESCRIPTION_LENGTH: usize = 23;
+
+// page 0 spends 6 bytes on the last page index, length and timestamp
+pub const AUTH_PAGE_0_DATA_LENGTH: usize = 17;
+pub const AUTH_PAGE_N_DATA_LENGTH: usize = 23;
+pub const AUTH_MAX_PAGES: usize = 16;
+
+pub fn parse_message_type(input: &[u8]) -> ParseResult<'_, RemoteIdMessage> {
+    match input.first() {
+        Some(header) => Ok((input, RemoteIdMessage::from(header >> 4))),
+        None => Err(ParseError::new(
+            ParseLayer::OdidMessage,
+            0,
+            ParseErrorReason::TooShort,
+        )),
+    }
+}
+
+pub fn parse_message_version(input: &[u8]) -> ParseResult<'_, ProtocolVersion> {
+    match input.first() {
+        Some(header) => Ok((input, ProtocolVersion::from(header & 0x0F))),
+        None => Err(ParseError::new(
+            ParseLayer::OdidMessage,
+            0,
+            ParseErrorReason::TooShort,
+        )),
+    }
+}
+
+/// Parses a single 25 byte ODID message, header included. The body is decoded with the
+/// layout of the protocol version in the header.
+pub fn parse_message(input: &[u8]) -> ParseResult<'_, OdidMessage> {
+    if input.len() < ODID_MESSAGE_SIZE {
+        return Err(ParseError::new(
+            ParseLayer::OdidMessage,
+            input.len(),
+            ParseErrorReason::TooShort,
+        ));
+    }
+
+    let message_type_and_version = input[0];
+    let body = &input[1..ODID_MESSAGE_SIZE];
+    let version = ProtocolVersion::from(message_type_and_version & 0x0F);
+
+    // body errors are reported relative to the start of the message
+    let shift_header = |error: ParseError| error.shifted(1);
+
+    let message = match RemoteIdMessage::from(message_type_and_version >> 4) {
+        RemoteIdMessage::BasicId => OdidMessage::BasicId(
+            parse_basic_id_for_version(body, version)
+                .map_err(shift_header)?
+                .1,
+        ),
+        RemoteIdMessage::Location => {
+            OdidMessage::Location(parse_location(body).map_err(shift_header)?.1)
+        }
+        RemoteIdMessage::Authentication => {
+            OdidMessage::Authentication(parse_authentication(body).map_err(shift_header)?.1)
+        }
+        RemoteIdMessage::SelfId => {
+            OdidMessage::SelfId(parse_self_id(body).map_err(shift_header)?.1)
+        }
+        RemoteIdMessage::SystemMessage => OdidMessage::SystemMessage(
+            parse_system_message_for_version(body, version)
+                .map_err(shift_header)?
+                .1,
+        ),
+        RemoteIdMessage::OperatorId => {
+            OdidMessage::OperatorId(parse_operator_id(body).map_err(shift_header)?.1)
+        }
+        // message packs can't be nested
+        RemoteIdMessage::MessagePack | RemoteIdMessage::Unknown => {
+            OdidMessage::Unknown(message_type_and_version >> 4)
+        }
+    };
+
+    Ok((&input[ODID_MESSAGE_SIZE..], message))
+}
+
+/// Parses a Message Pack (type 0xF), as carried by Wi-Fi beacons, Wi-Fi NAN, Bluetooth 5
+/// Long Range and Network Remote ID.
+pub fn parse_message_pack(input: &[u8]) -> ParseResult<'_, MessagePack> {
+    let (mut rest, (message_type_and_version, single_msg_size, num_messages)) =
+        parse_with_layer(ParseLayer::MessagePack, input, |input| {
+            let (input, message_type_and_version) = le_u8(input)?;
+            let (input, single_msg_size) = le_u8(input)?;
+            let (input, num_messages) = le_u8(input)?;
+
+            Ok((
+                input,
+                (message_type_and_version, single_msg_size, num_messages),
+            ))
+        })?;
+
+    if RemoteIdMessage::from(message_type_and_version >> 4) != RemoteIdMessage::MessagePack {
+        return Err(ParseError::new(
+            ParseLayer::MessagePack,
+            0,
+            ParseErrorReason::UnexpectedType,
+        ));
+    }
+
+    if single_msg_size as usize != ODID_MESSAGE_SIZE
+        || num_messages as usize > ODID_PACK_MAX_MESSAGES
+        || num_messages as usize * ODID_MESSAGE_SIZE > rest.len()
+    {
+        return Err(ParseError::new(
+            ParseLayer::MessagePack,
+            1,
+            ParseErrorReason::InvalidLength,
+        ));
+    }
+
+    let mut messages = ArrayVec::new();
+
+    for _ in 0..num_messages {
+        let offset = input.len() - rest.len();
+        let (new_rest, message) = parse_message(rest).map_err(|error| error.shifted(offset))?;
+
+        // num_messages was checked against the pack capacity above
+        messages.push(message);
+        rest = new_rest;
+    }
+
+    Ok((
+        rest,
+        MessagePack {
+            version: message_type_and_version & 0x0F,
+            single_msg_size,
+            num_messages,
+            messages,
+        },
+    ))
+}
+
+pub fn parse_basic_id(input: &[u8]) -> ParseResult<'_, BasicId> {
+    parse_basic_id_for_version(input, ProtocolVersion::F3411v22a)
+}
+
+pub fn parse_basic_id_for_version(
+    input: &[u8],
+    version: ProtocolVersion,
+) -> ParseResult<'_, BasicId> {
+    parse_with_layer(ParseLayer::OdidMessage, input, |input| {
+        let (input, id_and_ua_type) = le_u8(input)?;
+        let id_type = (id_and_ua_type & 0xF0) >> 4;
+        let ua_type = id_and_ua_type & 0x0F;
+
+        // ID types above UTM assigned UUID were reserved before F3411-22a
+        let uas_id_type = match version {
+            ProtocolVersion::F3411v19 | ProtocolVersion::F3411v20 if id_type > 3 => {
+                UasIdType::Other(id_type)
+            }
+            _ => UasIdType::from(id_type),
+        };
+
+        let (input, uas_id) = take(UAS_ID_LENGTH)(input)?;
+        let (input, _reserved) = take(3usize)(input)?;
+
+        // session IDs and UUIDs are binary, everything else is null padded ASCII
+        let (uas_id, session_id_type) = match uas_id_type {
+            UasIdType::SpecificSessionId => (
+                encode_hex(&uas_id[1..]),
+                Some(SessionIdType::from(uas_id[0])),
+            ),
+            UasIdType::UtmId => (encode_hex(&uas_id[..UTM_ID_LENGTH]), None),
+            _ => (ascii_field(uas_id), None),
+        };
+
+        Ok((
+            input,
+            BasicId {
+                uas_id_type,
+                ua_type: UaType::from(ua_type),
+                uas_id,
+                session_id_type,
+            },
+        ))
+    })
+}
+
+/// Copies a null padded ASCII field up to the first null. Bytes outside ASCII are
+/// replaced with `?` so the field always fits its fixed capacity.
+fn ascii_field<const N: usize>(bytes: &[u8]) -> ArrayString<N> {
+    let mut field = ArrayString::new();
+
+    for b in bytes.iter().take_while(|b| **b != 0x0).take(N) {
+        field.push(if b.is_ascii() { *b as char } else { '?' });
+    }
+
+    field
+}
+
+/// Lowercase hex of as many leading bytes as fit in `N` characters.
+pub(super) fn encode_hex<const N: usize>(bytes: &[u8]) -> ArrayString<N> {
+    let mut hex = ArrayString::new();
+
+    for b in bytes.iter().take(N / 2) {
+        // two hex digits per byte always fit after the take above
+        let _ = write!(hex, "{:02x}", b);
+    }
+
+    hex
+}
+
+pub(super) fn decode_hex<const N: usize>(hex: &str) -> Option<ArrayVec<u8, N>> {
+    let mut bytes = ArrayVec::new();
+
+    for pair in hex.as_bytes().chunks(2) {
+        let byte = match pair {
+            [high, low] => {
+                u8::from_str_radix(core::str::from_utf8(&[*high, *low]).ok()?, 16).ok()?
+            }
+            _ => return None,
+        };
+
+        bytes.try_push(byte).ok()?;
+    }
+
+    Some(bytes)
+}
+
+pub fn parse_system_message(input: &[u8]) -> ParseResult<'_, SystemMessage> {
+    parse_system_message_for_version(input, ProtocolVersion::F3411v22a)
+}
+
+/// F3411-19 ends the System message after the area floor; the classification, operator
+/// altitude and timestamp fields were added in F3411-20 and are left undeclared for v0.
+pub fn parse_system_message_for_version(
+    input: &[u8],
+    version: ProtocolVersion,
+) -> ParseResult<'_, SystemMessage> {
+    parse_with_layer(ParseLayer::OdidMessage, input, |input| {
+        let (input, flags) = le_u8(input)?;
+        let operator_location_type = OperatorLocationType::from(flags & 0x03);
+
+        let (input, operator_latitude_int) = le_i32(input)?;
+        let (input, operator_longitude_int) = le_i32(input)?;
+        let (input, area_count) = le_u16(input)?;
+        let (input, area_radius) = le_u8(input)?;
+        let (input, area_ceiling) = le_u16(input)?;
+        let (input, area_floor) = le_u16(input)?;
+
+        let (
+            input,
+            (classification_type, ua_category, ua_class, operator_altitude_geodetic, timestamp),
+        ) = match version {
+            ProtocolVersion::F3411v19 => {
+                let (input, _reserved) = take(7usize)(input)?;
+
+                (
+                    input,
+                    (
+                        ClassificationType::Undeclared,
+                        UaCategory::Undeclared,
+                        UaClass::Undeclared,
+                        0,
+                        0,
+                    ),
+                )
+            }
+            _ => {
+                let (input, category_class) = le_u8(input)?;
+                let (input, operator_altitude_geodetic) = le_u16(input)?;
+                let (input, timestamp) = le_u32(input)?;
+
+                (
+                    input,
+                    (
+                        ClassificationType::from((flags & 0x1C) >> 2),
+                        UaCategory::from((category_class & 0xF0) >> 4),
+                        UaClass::from(category_class & 0x0F),
+                        operator_altitude_geodetic,
+                        timestamp,
+                    ),
+                )
+            }
+        };
+
+        let (input, _reserved) = le_u8(input)?;
+
+        Ok((
+            input,
+            SystemMessage {
+                operator_location_type,
+                classification_type,
+                operator_latitude_int,
+                operator_longitude_int,
+                area_count,
+                area_radius,
+                area_ceiling,
+                area_floor,
+                ua_category,
+                ua_class,
+                operator_altitude_geodetic,
+                timestamp,
+            },
+        ))
+    })
+}
+
+pub fn parse_operator_id(input: &[u8]) -> ParseResult<'_, Operator> {
+    parse_with_layer(ParseLayer::OdidMessage, input, |input| {
+        let (input, operator_id_type) = le_u8(input)?;
+        let (input, operator_id) = take(OPERATOR_ID_LENGTH)(input)?;
+        let (input, _reserved) = take(3usize)(input)?;
+
+        Ok((
+            input,
+            Operator {
+                operator_id_type,
+                operator_id: ascii_field(operator_id),
+            },
+        ))
+    })
+}
+
+pub fn parse_self_id(input: &[u8]) -> ParseResult<'_, SelfId> {
+    parse_with_layer(ParseLayer::OdidMessage, input, |input| {
+        let (input, description_type) = le_u8(input)?;
+        let (input, description) = take(SELF_ID_DESCRIPTION_LENGTH)(input)?;
+
+        Ok((
+            input,
+            SelfId {
+                description_type: SelfIdDescriptionType::from(description_type),
+                description: ascii_field(description),
+            },
+        ))
+    })
+}
+
+pub fn parse_authentication(input: &[u8]) -> ParseResult<'_, Authentication> {
+    parse_with_layer(ParseLayer::OdidMessage, input, |input| {
+        let (input, type_and_page) = le_u8(input)?;
+        let auth_type = (type_and_page & 0xF0) >> 4;
+        let page = type_and_page & 0x0F;
+
+        if page > 0 {
+            let (input, auth_data) = take(AUTH_PAGE_N_DATA_LENGTH)(input)?;
+
+            return Ok((
+                input,
+                Authentication {
+                    auth_type: AuthType::from(auth_type),
+                    page,
+                    last_page_index: None,
+                    length: None,
+                    timestamp: None,
+                    auth_data: auth_data.iter().copied().collect(),
+                },
+            ));
+        }
+
+        let (input, last_page_index) = le_u8(input)?;
+        let (input, length) = le_u8(input)?;
+        let (input, timestamp) = le_u32(input)?;
+        let (input, auth_data) = take(AUTH_PAGE_0_DATA_LENGTH)(input)?;
+
+        if last_page_index as usize >= AUTH_MAX_PAGES {
+            return Err(nom::Err::Failure(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+
+        Ok((
+            input,
+            Authentication {
+                auth_type: AuthType::from(auth_type),
+                page,
+                last_page_index: Some(last_page_index),
+                length: Some(length),
+                timestamp: Some(timestamp),
+                auth_data: auth_data.iter().copied().collect(),
+            },
+        ))
+    })
+}
+
+pub fn parse_location(input: &[u8]) -> ParseResult<'_, Location> {
+    parse_with_layer(ParseLayer::OdidMessage, input, |input| {
+        let (input, input_first_byte) = le_u8(input)?;
+
+        let status = (input_first_byte & 0xF0) >> 4;
+        let _reserved = input_first_byte & 0x08;
+        let height_type = (input_first_byte & 0x04) >> 2;
+        let ew_direction = (input_first_byte & 0x02) >> 1;
+        let speed_multiplier = input_first_byte & 0x01;
+
+        let (input, tracking_direction) = le_u8(input)?;
+        let (input, speed) = le_u8(input)?;
+        let (input, vertical_speed) = le_i8(input)?;
+        let (input, latitude_int) = le_i32(input)?;
+        let (input, longitude_int) = le_i32(input)?;
+        let (input, altitude_pressure) = le_u16(input)?;
+        let (input, altitude_geodetic) = le_u16(input)?;
+        let (input, height) = le_u16(input)?;
+        let (input, vert_hor_accuracy) = le_u8(input)?;
+        let (input, baroalt_speed_accuracy_flags) = le_u8(input)?;
+
+        let vertical_accuracy = (vert_hor_accuracy & 0xF0) >> 4;
+        let horizontal_accuracy = vert_hor_accuracy & 0x0F;
+        let barometric_altitude_accuracy = (baroalt_speed_accuracy_flags & 0xF0) >> 4;
+        let speed_accuracy = baroalt_speed_accuracy_flags & 0x0F;
+
+        let (input, timestamp) = le_u16(input)?;
+        let (input, timestamp_accuracy) = le_u8(input)?;
+        let (input, _reserved) = le_u8(input)?;
+
+        let timestamp_accuracy = timestamp_accuracy & 0x0F;
+
+        Ok((
+            input,
+            Location {
+                status,
+                height_type,
+                ew_direction,
+                speed_multiplier,
+                tracking_direction,
+                speed,
+                vertical_speed,
+                latitude_int,
+                longitude_int,
+                altitude_pressure,
+                altitude_geodetic,
+                height,
+                horizontal_accuracy,
+                vertical_accuracy,
+                barometric_altitude_accuracy,
+                speed_accuracy,
+                timestamp,
+                timestamp_accuracy,
+            },
+        ))
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::test_util::read_fixture;
+
+    #[test]
+    fn test_parse_message_type() {
+        let bytes = read_fixture("../fixtures/odid_system_packet.txt").unwrap();
+
+        let message_type: Option<RemoteIdMessage> = match parse_message_type(&bytes) {
+            Ok((_, message_type)) => Some(message_type),
+            Err(_) => None,
+        };
+
+        assert!(message_type.is_some());
+
+        let message_type = message_type.unwrap();
+
+        assert_eq!(message_type, RemoteIdMessage::SystemMessage);
+    }
+
+    #[test]
+    fn test_parse_basic_id() {
+        let bytes = read_fixture("../fixtures/odid_basic_id_packet.txt").unwrap();
+
+        // skip the message type and version header
+        let bytes = &bytes[1..];
+
+        let basic_id: Option<BasicId> = match parse_basic_id(bytes) {
+            Ok((_, basic_id)) => Some(basic_id),
+            Err(_) => None,
+        };
+
+        assert!(basic_id.is_some());
+
+        let basic_id = basic_id.unwrap();
+
+        assert_eq!(basic_id.uas_id_type, UasIdType::SerialNumber);
+        assert_eq!(basic_id.ua_type, UaType::HelicopterOrDrone);
+        assert_eq!(basic_id.uas_id.as_str(), "1787F04BM24010011039");
+    }
+
+    #[test]
+    fn test_parse_location_message() {
+        let bytes = read_fixture("../fixtures/odid_location_packet.txt").unwrap();
+        // skip the message type and version header
+        let bytes = &bytes[1..];
+
+        let location_message: Option<Location> = match parse_location(bytes) {
+            Ok((_, location_message)) => Some(location_message),
+            Err(e) => {
+                eprintln!("Failed to parse location message: {:?}", e);
+                None
+            }
+        };
+
+        assert!(location_message.is_some());
+
+        let location_message = location_message.unwrap();
+
+        assert_eq!(location_message.status, 0x1);
+        assert_eq!(location_message.height_type, 0x0);
+        assert_eq!(location_message.ew_direction, 0x0);
+        assert_eq!(location_message.speed_multiplier, 0x0);
+        assert_eq!(location_message.tracking_direction, 0x0);
+        assert_eq!(location_message.speed, 0x0);
+        assert_eq!(location_message.vertical_speed, 0x0);
+        assert_eq!(location_message.latitude_int, 358025790);
+        assert_eq!(location_message.longitude_int, -907109691);
+        assert_eq!(location_message.altitude_pressure, 0x0);
+        assert_eq!(location_message.altitude_geodetic, 2136);
+        assert_eq!(location_message.height, 2002);
+        assert_eq!(location_message.horizontal_accuracy, 0xb);
+        assert_eq!(location_message.vertical_accuracy, 0x4);
+        assert_eq!(location_message.barometric_altitude_accuracy, 0x0);
+        assert_eq!(location_message.speed_accuracy, 0x2);
+        assert_eq!(location_message.timestamp, 9701);
+        assert_eq!(location_message.timestamp_accuracy, 0x1);
+    }
+
+    #[test]
+    fn test_parse_system_message() {
+        let bytes = read_fixture("../fixtures/odid_system_packet.txt").unwrap();
+        // skip the message type and version header
+        let bytes = &bytes[1..];
+
+        let system_message: Option<SystemMessage> = match parse_system_message(bytes) {
+            Ok((_, system_message)) => Some(system_message),
+            Err(_) => None,
+        };
+
+        assert!(system_message.is_some());
+
+        let system_message = system_message.unwrap();
+
+        assert_eq!(
+            system_message.operator_location_type,
+            OperatorLocationType::TakeOff
+        );
+        assert_eq!(
+            system_message.classification_type,
+            ClassificationType::Undeclared
+        );
+        assert_eq!(system_message.operator_latitude_int, 358025741);
+        assert_eq!(system_message.operator_longitude_int, -907109656);
+        assert_eq!(system_message.area_count, 1);
+        assert_eq!(system_message.area_radius, 0x0);
+        assert_eq!(system_message.area_ceiling, 0x0);
+        assert_eq!(system_message.area_floor, 0x0);
+        assert_eq!(system_message.ua_category, UaCategory::Undeclared);
+        assert_eq!(system_message.ua_class, UaClass::Undeclared);
+        assert_eq!(system_message.operator_altitude_geodetic, 2134);
+        assert_eq!(system_message.timestamp, 173376968);
+    }
+
+    #[test]
+    fn test_parse_eu_system_message() {
+        let bytes = read_fixture("../fixtures/odid_system_packet.txt").unwrap();
+        let mut bytes = bytes[1..].to_vec();
+
+        // EU classification, specific category, class 2
+        bytes[0] |= 0x01 << 2;
+        bytes[16] = 0x23;
+
+        let (_, system_message) = parse_system_message(&bytes).unwrap();
+
+        assert_eq!(
+            system_message.classification_type,
+            ClassificationType::EuropeanUnion
+        );
+        assert_eq!(system_message.ua_category, UaCategory::Specific);
+        assert_eq!(system_message.ua_class, UaClass::Class2);
+    }
+
+    #[test]
+    fn test_parse_system_message_v0() {
+        let mut bytes = read_fixture("../fixtures/odid_system_packet.txt").unwrap();
+
+        // F3411-19 header, the trailing fields are reserved in that version
+        bytes[0] = 0x40;
+        bytes[17] = 0x23;
+
+        assert_eq!(
+            parse_message_version(&bytes).map(|(_, version)| version),
+            Ok(ProtocolVersion::F3411v19)
+        );
+
+        let system_message = match parse_message(&bytes) {
+            Ok((_, OdidMessage::SystemMessage(system_message))) => system_message,
+            result => panic!("Expected system message, got {:?}", result),
+        };
+
+        assert_eq!(system_message.operator_latitude_int, 358025741);
+        assert_eq!(system_message.operator_longitude_int, -907109656);
+        assert_eq!(system_message.ua_category, UaCategory::Undeclared);
+        assert_eq!(system_message.ua_class, UaClass::Undeclared);
+        assert_eq!(system_message.operator_altitude_geodetic, 0);
+        assert_eq!(system_message.timestamp, 0);
+    }
+
+    #[test]
+    fn test_parse_basic_id_reserved_type_before_v2() {
+        let mut bytes = read_fixture("../fixtures/odid_basic_id_packet.txt").unwrap();
+
+        // F3411-20 header with ID type 4, which is only defined from F3411-22a
+        bytes[0] = 0x01;
+        bytes[1] = 0x42;
+
+        let basic_id = match parse_message(&bytes) {
+            Ok((_, OdidMessage::BasicId(basic_id))) => basic_id,
+            result => panic!("Expected basic id, got {:?}", result),
+        };
+
+        assert_eq!(basic_id.uas_id_type, UasIdType::Other(4));
+        assert_eq!(basic_id.ua_type, UaType::HelicopterOrDrone);
+    }
+
+    #[test]
+    fn test_parse_self_id() {
+        let bytes = read_fixture("../fixtures/odid_self_id_packet.txt").unwrap();
+
+        let message_type = parse_message_type(&bytes).map(|(_, message_type)| message_type);
+
+        assert_eq!(message_type, Ok(RemoteIdMessage::SelfId));
+
+        // skip the message type and version header
+        let self_id: Option<SelfId> = match parse_self_id(&bytes[1..]) {
+            Ok((_, self_id)) => Some(self_id),
+            Err(_) => None,
+        };
+
+        assert!(self_id.is_some());
+
+        let self_id = self_id.unwrap();
+
+        assert_eq!(self_id.description_type, SelfIdDescriptionType::Text);
+        assert_eq!(self_id.description.as_str(), "Jojo Test");
+    }
+
+    #[test]
+    fn test_parse_authentication_pages() {
+        // auth type 3 (message set signature), page 0 of 0..=1, 30 bytes, timestamp 0x01020304
+        let mut page_0 = vec![0x30, 0x01, 30, 0x04, 0x03, 0x02, 0x01];
+        page_0.extend_from_slice(&[0xaa; AUTH_PAGE_0_DATA_LENGTH]);
+
+        let (_, authentication) = parse_authentication(&page_0).unwrap();
+
+        assert_eq!(authentication.auth_type, AuthType::MessageSetSignature);
+        assert_eq!(authentication.page, 0);
+        assert_eq!(authentication.last_page_index, Some(1));
+        assert_eq!(authentication.length, Some(30));
+        assert_eq!(authentication.timestamp, Some(0x01020304));
+        assert_eq!(authentication.auth_data.len(), AUTH_PAGE_0_DATA_LENGTH);
+
+        let mut page_1 = vec![0x31];
+        page_1.extend_from_slice(&[0xbb; AUTH_PAGE_N_DATA_LENGTH]);
+
+        let (_, authentication) = parse_authentication(&page_1).unwrap();
+
+        assert_eq!(authentication.page, 1);
+        assert_eq!(authentication.last_page_index, None);
+        assert_eq!(
+            authentication.auth_data.as_slice(),
+            &[0xbb; AUTH_PAGE_N_DATA_LENGTH]
+        );
+
+        assert!(parse_authentication(&page_1[..10]).is_err());
+    }
+
+    #[test]
+    fn test_parse_message_pack() {
+        let bytes = read_fixture("../fixtures/odid_message_pack.txt").unwrap();
+
+        let message_pack: Option<MessagePack> = match parse_message_pack(&bytes) {
+            Ok((_, message_pack)) => Some(message_pack),
+            Err(e) => {
+                eprintln!("Failed to parse message pack: {:?}", e);
+                None
+            }
+        };
+
+        assert!(message_pack.is_some());
+
+        let message_pack = message_pack.unwrap();
+
+        assert_eq!(message_pack.version, 0x2);
+        assert_eq!(message_pack.single_msg_size, 25);
+        assert_eq!(message_pack.num_messages, 4);
+        assert_eq!(message_pack.messages.len(), 4);
+
+        match &message_pack.messages[0] {
+            OdidMessage::BasicId(basic_id) => {
+                assert_eq!(basic_id.uas_id.as_str(), "1787F04BM24010011039")
+            }
+            message => panic!("Expected basic id, got {:?}", message),
+        }
+
+        assert!(matches!(
+            message_pack.messages[1],
+            OdidMessage::SystemMessage(_)
+        ));
+
+        match &message_pack.messages[2] {
+            OdidMessage::Location(location) => {
+                assert_eq!(location.latitude_int, 358025796);
+                assert_eq!(location.longitude_int, -907110086);
+            }
+            message => panic!("Expected location, got {:?}", message),
+        }
+
+        match &message_pack.messages[3] {
+            OdidMessage::SelfId(self_id) => assert_eq!(self_id.description.as_str(), "Jojo Test"),
+            message => panic!("Expected self id, got {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_parse_message_pack_rejects_bad_sizes() {
+        let bytes = read_fixture("../fixtures/odid_message_pack.txt").unwrap();
+
+        // single message size must be 25
+        let mut bad_size = bytes.clone();
+        bad_size[1] = 24;
+        assert!(parse_message_pack(&bad_size).is_err());
+
+        // no more than 9 messages per pack
+        let mut too_many = bytes.clone();
+        too_many[2] = 10;
+        assert!(parse_message_pack(&too_many).is_err());
+
+        // announced messages must fit in the remaining input
+        assert!(parse_message_pack(&bytes[..bytes.len() - 1]).is_err());
+
+        // not a message pack
+        assert!(parse_message_pack(&bytes[3..]).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors_carry_layer_and_offset() {
+        let bytes = read_fixture("../fixtures/odid_message_pack.txt").unwrap();
+
+        assert_eq!(
+            parse_message_pack(&bytes[3..]).unwrap_err(),
+            ParseError::new(ParseLayer::MessagePack, 0, ParseErrorReason::UnexpectedType)
+        );
+
+        // turn the third message into an authentication page 0 announcing 17 pages
+        let mut bad_page = bytes.clone();
+        let message = 3 + 2 * ODID_MESSAGE_SIZE;
+        bad_page[message] = 0x22;
+        bad_page[message + 1] = 0x00;
+        bad_page[message + 2] = 16;
+
+        let error = parse_message_pack(&bad_page).unwrap_err();
+
+        assert_eq!(error.layer, ParseLayer::OdidMessage);
+        assert_eq!(error.reason, ParseErrorReason::InvalidValue);
+        assert!(error.offset > message && error.offset <= message + ODID_MESSAGE_SIZE);
+
+        assert_eq!(
+            parse_message(&bytes[3..20]).unwrap_err(),
+            ParseError::new(ParseLayer::OdidMessage, 17, ParseErrorReason::TooShort)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+const MANUFACTURER_CODE_LENGTH: usize = 4;
+const SERIAL_NUMBER_MAX_LENGTH: usize = 20;
+
+#[cfg(feature = "std")]
+const BUNDLED_MANUFACTURER_CODES: &str = include_str!("manufacturer_codes.csv");
+
+#[cfg(feature = "std")]
+static INSTALLED_REGISTRY: OnceLock<ManufacturerRegistry> = OnceLock::new();
+
+/// An ANSI/CTA-2063-A serial number: a 4 character manufacturer code, a length character
+/// (1-9, A-F) and the manufacturer's serial of that length.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SerialNumber {
+    pub manufacturer_code: String,
+    pub serial: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SerialNumberError {
+    TooShort,
+    TooLong,
+    InvalidCharacter(char),
+    InvalidLengthCode(char),
+    LengthMismatch { expected: usize, actual: usize },
+}
+
+// digits and upper case letters, O and I are excluded to avoid confusion with 0 and 1
+fn is_serial_character(c: char) -> bool {
+    (c.is_ascii_digit() || c.is_ascii_uppercase()) && c != 'O' && c != 'I'
+}
+
+pub fn parse_serial_number(uas_id: &str) -> Result<SerialNumber, SerialNumberError> {
+    if uas_id.len() <= MANUFACTURER_CODE_LENGTH + 1 {
+        return Err(SerialNumberError::TooShort);
+    }
+
+    if uas_id.len() > SERIAL_NUMBER_MAX_LENGTH {
+        return Err(SerialNumberError::TooLong);
+    }
+
+    if let Some(c) = uas_id.chars().find(|c| !is_serial_character(*c)) {
+        return Err(SerialNumberError::InvalidCharacter(c));
+    }
+
+    let manufacturer_code = &uas_id[..MANUFACTURER_CODE_LENGTH];
+    let length_code = uas_id[MANUFACTURER_CODE_LENGTH..].chars().next().unwrap();
+    let serial = &uas_id[MANUFACTURER_CODE_LENGTH + 1..];
+
+    let expected = match length_code.to_digit(16) {
+        Some(length) if length > 0 => length as usize,
+        _ => return Err(SerialNumberError::InvalidLengthCode(length_code)),
+    };
+
+    if serial.len() != expected {
+        return Err(SerialNumberError::LengthMismatch {
+            expected,
+            actual: serial.len(),
+        });
+    }
+
+    Ok(SerialNumber {
+        manufacturer_code: manufacturer_code.to_string(),
+        serial: serial.to_string(),
+    })
+}
+
+/// Maps manufacturer codes to manufacturer names.
+#[derive(Debug, Clone, Default)]
+pub struct ManufacturerRegistry {
+    manufacturers: BTreeMap<String, String>,
+}
+
+impl ManufacturerRegistry {
+    /// Reads `code,manufacturer` lines, skipping blank lines and `#` comments.
+    pub fn from_csv(csv: &str) -> Self {
+        let manufacturers = csv
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once(','))
+            .map(|(code, manufacturer)| (code.trim().to_string(), manufacturer.trim().to_string()))
+            .collect();
+
+        ManufacturerRegistry { manufacturers }
+    }
+
+    /// The registry bundled with this build.
+    #[cfg(feature = "std")]
+    pub fn bundled() -> &'static ManufacturerRegistry {
+        static REGISTRY: OnceLock<ManufacturerRegistry> = OnceLock::new();
+
+        REGISTRY.get_or_init(|| ManufacturerRegistry::from_csv(BUNDLED_MANUFACTURER_CODES))
+    }
+
+    /// Makes `registry` the one `current` returns, failing if one is already installed.
+    #[cfg(feature = "std")]
+    pub fn install(registry: ManufacturerRegistry) -> Result<(), ManufacturerRegistry> {
+        INSTALLED_REGISTRY.set(registry)
+    }
+
+    /// The installed registry, or the bundled one if none was installed.
+    #[cfg(feature = "std")]
+    pub fn current() -> &'static ManufacturerRegistry {
+        INSTALLED_REGISTRY
+            .get()
+            .unwrap_or_else(ManufacturerRegistry::bundled)
+    }
+
+    /// Adds the codes of `other`, its names replacing ours for codes both know.
+    pub fn extend(&mut self, other: ManufacturerRegistry) {
+        self.manufacturers.extend(other.manufacturers);
+    }
+
+    pub fn lookup(&self, manufacturer_code: &str) -> Option<&str> {
+        self.manufacturers
+            .get(manufacturer_code)
+            .map(|manufacturer| manufacturer.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_serial_number() {
+        let serial_number = parse_serial_number("1787F04BM24010011039").unwrap();
+
+        assert_eq!(serial_number.manufacturer_code, "1787");
+        assert_eq!(serial_number.serial, "04BM24010011039");
+    }
+
+    #[test]
+    fn test_parse_malformed_serial_numbers() {
+        assert_eq!(
+            parse_serial_number("1581"),
+            Err(SerialNumberError::TooShort)
+        );
+        assert_eq!(
+            parse_serial_number("1581F0123456789ABCDEFG"),
+            Err(SerialNumberError::TooLong)
+        );
+        assert_eq!(
+            parse_serial_number("1581504IA1"),
+            Err(SerialNumberError::InvalidCharacter('I'))
+        );
+        assert_eq!(
+            parse_serial_number("1581G04BM2"),
+            Err(SerialNumberError::InvalidLengthCode('G'))
+        );
+        assert_eq!(
+            parse_serial_number("1581504BM24"),
+            Err(SerialNumberError::LengthMismatch {
+                expected: 5,
+                actual: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_manufacturer_registry() {
+        let registry = ManufacturerRegistry::from_csv("# comment\n\n1581,DJI\nABCD, Example \n");
+
+        assert_eq!(registry.lookup("1581"), Some("DJI"));
+        assert_eq!(registry.lookup("ABCD"), Some("Example"));
+        assert_eq!(registry.lookup("0000"), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bundled_manufacturer_registry() {
+        assert_eq!(ManufacturerRegistry::bundled().lookup("1581"), Some("DJI"));
+    }
+
+    #[test]
+    fn test_extend_manufacturer_registry() {
+        let mut registry = ManufacturerRegistry::from_csv("1581,DJI\nABCD,Example\n");
+
+        registry.extend(ManufacturerRegistry::from_csv("ABCD,Renamed\n1234,Other\n"));
+
+        assert_eq!(registry.lookup("1581"), Some("DJI"));
+        assert_eq!(registry.lookup("ABCD"), Some("Renamed"));
+        assert_eq!(registry.lookup("1234"), Some("Other"));
+    }
+
+    // the only test installing a registry, the rest look up the bundled one directly
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_install_manufacturer_registry() {
+        let mut registry = ManufacturerRegistry::bundled().clone();
+
+        registry.extend(ManufacturerRegistry::from_csv("ABCD,Example\n"));
+
+        assert!(ManufacturerRegistry::install(registry).is_ok());
+        assert_eq!(ManufacturerRegistry::current().lookup("1581"), Some("DJI"));
+        assert_eq!(
+            ManufacturerRegistry::current().lookup("ABCD"),
+            Some("Example")
+        );
+        assert!(ManufacturerRegistry::install(ManufacturerRegistry::default()).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    parse_serial_number, AuthType, Authentication, BasicId, ClassificationType, HorizontalAccuracy,
+    Location, OdidMessage, OperationalStatus, Operator, OperatorLocationType, RemoteIdMessage,
+    SelfId, SelfIdDescriptionType, SerialNumberError, SpeedAccuracy, SystemMessage, UaCategory,
+    UaClass, UasIdType, VerticalAccuracy,
+};
+
+const LATITUDE_INT_MAX: i32 = 900_000_000;
+const LONGITUDE_INT_MAX: i32 = 1_800_000_000;
+
+// track direction 361 is the encoding for "unknown"
+const TRACK_DIRECTION_UNKNOWN: u16 = 361;
+const VERTICAL_SPEED_UNKNOWN: i8 = 126;
+const VERTICAL_SPEED_MAX: i8 = 124;
+const TIMESTAMP_UNKNOWN: u16 = 0xFFFF;
+const TIMESTAMP_MAX: u16 = 36000;
+
+// values past the reserved ranges are set aside for private use
+const AUTH_TYPE_PRIVATE_START: u8 = 0xA;
+const SELF_ID_TYPE_PRIVATE_START: u8 = 201;
+const OPERATOR_ID_TYPE_PRIVATE_START: u8 = 201;
+
+/// A decoded value that the specification does not allow.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Violation {
+    ReservedUasIdType(u8),
+    EmptyUasId,
+    InvalidSerialNumber(SerialNumberError),
+    ReservedOperationalStatus(u8),
+    TrackDirectionOutOfRange(u16),
+    VerticalSpeedOutOfRange(i8),
+    LatitudeOutOfRange(i32),
+    LongitudeOutOfRange(i32),
+    ReservedHorizontalAccuracy(u8),
+    ReservedVerticalAccuracy(u8),
+    ReservedBarometricAltitudeAccuracy(u8),
+    ReservedSpeedAccuracy(u8),
+    TimestampOutOfRange(u16),
+    ReservedAuthType(u8),
+    ReservedSelfIdType(u8),
+    ReservedOperatorIdType(u8),
+    InvalidOperatorIdCharacter(char),
+    ReservedOperatorLocationType(u8),
+    ReservedClassificationType(u8),
+    ReservedUaCategory(u8),
+    ReservedUaClass(u8),
+    OperatorLatitudeOutOfRange(i32),
+    OperatorLongitudeOutOfRange(i32),
+    MissingMessage(RemoteIdMessage),
+}
+
+pub fn validate_basic_id(basic_id: &BasicId) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    if let UasIdType::Other(value) = basic_id.uas_id_type {
+        violations.push(Violation::ReservedUasIdType(value));
+    }
+
+    if basic_id.uas_id.is_empty() && basic_id.uas_id_type != UasIdType::None {
+        violations.push(Violation::EmptyUasId);
+    }
+
+    if basic_id.uas_id_type == UasIdType::SerialNumber {
+        if let Err(error) = parse_serial_number(&basic_id.uas_id) {
+            violations.push(Violation::InvalidSerialNumber(error));
+        }
+    }
+
+    violations
+}
+
+pub fn validate_location(location: &Location) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    if let OperationalStatus::Other(value) = OperationalStatus::from(location.status) {
+        violations.push(Violation::ReservedOperationalStatus(value));
+    }
+
+    let track_direction = location.tracking_direction as u16 + 180 * location.ew_direction as u16;
+
+    if track_direction >= 360 && track_direction != TRACK_DIRECTION_UNKNOWN {
+        violations.push(Violation::TrackDirectionOutOfRange(track_direction));
+    }
+
+    if location.vertical_speed != VERTICAL_SPEED_UNKNOWN
+        && !(-VERTICAL_SPEED_MAX..=VERTICAL_SPEED_MAX).contains(&location.vertical_speed)
+    {
+        violations.push(Violation::VerticalSpeedOutOfRange(location.vertical_speed));
+    }
+
+    if !(-LATITUDE_INT_MAX..=LATITUDE_INT_MAX).contains(&location.latitude_int) {
+        violations.push(Violation::LatitudeOutOfRange(location.latitude_int));
+    }
+
+    if !(-LONGITUDE_INT_MAX..=LONGITUDE_INT_MAX).contains(&location.longitude_int) {
+        violations.push(Violation::LongitudeOutOfRange(location.longitude_int));
+    }
+
+    if let HorizontalAccuracy::Other(value) = HorizontalAccuracy::from(location.horizontal_accuracy)
+    {
+        violations.push(Violation::ReservedHorizontalAccuracy(value));
+    }
+
+    if let VerticalAccuracy::Other(value) = VerticalAccuracy::from(location.vertical_accuracy) {
+        violations.push(Violation::ReservedVerticalAccuracy(value));
+    }
+
+    if let VerticalAccuracy::Other(value) =
+        VerticalAccuracy::from(location.barometric_altitude_accuracy)
+    {
+        violations.push(Violation::ReservedBarometricAltitudeAccuracy(value));
+    }
+
+    if let SpeedAccuracy::Other(value) = SpeedAccuracy::from(location.speed_accuracy) {
+        violations.push(Violation::ReservedSpeedAccuracy(value));
+    }
+
+    if location.timestamp != TIMESTAMP_UNKNOWN && location.timestamp > TIMESTAMP_MAX {
+        violations.push(Violation::TimestampOutOfRange(location.timestamp));
+    }
+
+    violations
+}
+
+pub fn validate_authentication(authentication: &Authentication) -> Vec<Violation> {
+    match authentication.auth_type {
+        AuthType::Other(value) if value < AUTH_TYPE_PRIVATE_START => {
+            vec![Violation::ReservedAuthType(value)]
+        }
+        _ => vec![],
+    }
+}
+
+pub fn validate_self_id(self_id: &SelfId) -> Vec<Violation> {
+    match self_id.description_type {
+        SelfIdDescriptionType::Other(value) if value < SELF_ID_TYPE_PRIVATE_START => {
+            vec![Violation::ReservedSelfIdType(value)]
+        }
+        _ => vec![],
+    }
+}
+
+/// The operator ID is ASCII text, bytes outside ASCII come out of the parser as `?`.
+pub fn validate_operator(operator: &Operator) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    // type 0 is the operator ID, the rest up to private use is reserved
+    if operator.operator_id_type != 0 && operator.operator_id_type < OPERATOR_ID_TYPE_PRIVATE_START
+    {
+        violations.push(Violation::ReservedOperatorIdType(operator.operator_id_type));
+    }
+
+    if let Some(c) = operator
+        .operator_id
+        .chars()
+        .find(|c| !c.is_ascii_graphic() || *c == '?')
+    {
+        violations.push(Violation::InvalidOperatorIdCharacter(c));
+    }
+
+    violations
+}
+
+pub fn validate_system_message(system_message: &SystemMessage) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    if let OperatorLocationType::Other(value) = system_message.operator_location_type {
+        violations.push(Violation::ReservedOperatorLocationType(value));
+    }
+
+    if let ClassificationType::Other(value) = system_message.classification_type {
+        violations.push(Violation::ReservedClassificationType(value));
+    }
+
+    if let UaCategory::Other(value) = system_message.ua_category {
+        violations.push(Violation::ReservedUaCategory(value));
+    }
+
+    if let UaClass::Other(value) = system_message.ua_class {
+        violations.push(Violation::ReservedUaClass(value));
+    }
+
+    if !(-LATITUDE_INT_MAX..=LATITUDE_INT_MAX).contains(&system_message.operator_latitude_int) {
+        violations.push(Violation::OperatorLatitudeOutOfRange(
+            system_message.operator_latitude_int,
+        ));
+    }
+
+    if !(-LONGITUDE_INT_MAX..=LONGITUDE_INT_MAX).contains(&system_message.operator_longitude_int) {
+        violations.push(Violation::OperatorLongitudeOutOfRange(
+            system_message.operator_longitude_int,
+        ));
+    }
+
+    violations
+}
+
+/// Checks a single decoded message against the value ranges of F3411-22a.
+pub fn validate_message(message: &OdidMessage) -> Vec<Violation> {
+    match message {
+        OdidMessage::BasicId(basic_id) => validate_basic_id(basic_id),
+        OdidMessage::Location(location) => validate_location(location),
+        OdidMessage::Authentication(authentication) => validate_authentication(authentication),
+        OdidMessage::SelfId(self_id) => validate_self_id(self_id),
+        OdidMessage::SystemMessage(system_message) => validate_system_message(system_message),
+        OdidMessage::OperatorId(operator) => validate_operator(operator),
+        OdidMessage::Unknown(_) => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_fixture;
+    use crate::{encode_operator_id, parse_message, ProtocolVersion};
+    use arrayvec::ArrayString;
+
+    #[test]
+    fn test_validate_fixtures() {
+        for fixture in [
+            "../fixtures/odid_basic_id_packet.txt",
+            "../fixtures/odid_location_packet.txt",
+            "../fixtures/odid_system_packet.txt",
+            "../fixtures/odid_self_id_packet.txt",
+        ] {
+            let bytes = read_fixture(fixture).unwrap();
+            let (_, message) = parse_message(&bytes).unwrap();
+
+            assert_eq!(validate_message(&message), vec![], "{}", fixture);
+        }
+    }
+
+    #[test]
+    fn test_validate_location_violations() {
+        let bytes = read_fixture("../fixtures/odid_location_packet.txt").unwrap();
+
+        let mut location = match parse_message(&bytes) {
+            Ok((_, OdidMessage::Location(location))) => location,
+            result => panic!("Expected location, got {:?}", result),
+        };
+
+        location.status = 7;
+        location.ew_direction = 1;
+        location.tracking_direction = 185;
+        location.latitude_int = 900_000_001;
+        location.horizontal_accuracy = 13;
+        location.timestamp = 36001;
+        // 63.75 m/s at the low multiplier, not reserved
+        location.speed_multiplier = 0;
+        location.speed = 255;
+
+        assert_eq!(
+            validate_location(&location),
+            vec![
+                Violation::ReservedOperationalStatus(7),
+                Violation::TrackDirectionOutOfRange(365),
+                Violation::LatitudeOutOfRange(900_000_001),
+                Violation::ReservedHorizontalAccuracy(13),
+                Violation::TimestampOutOfRange(36001),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_operator_id() {
+        let mut bytes = encode_operator_id(
+            &Operator {
+                operator_id_type: 0,
+                operator_id: ArrayString::from("FIN87astrdge12k8").unwrap(),
+            },
+            ProtocolVersion::F3411v22a,
+        );
+
+        let (_, message) = parse_message(&bytes).unwrap();
+        assert_eq!(validate_message(&message), vec![]);
+
+        bytes[1] = 5;
+        bytes[6] = 0xe9;
+
+        let (_, message) = parse_message(&bytes).unwrap();
+        assert_eq!(
+            validate_message(&message),
+            vec![
+                Violation::ReservedOperatorIdType(5),
+                Violation::InvalidOperatorIdCharacter('?'),
+            ]
+        );
+
+        // private use
+        bytes[1] = 201;
+        bytes[6] = b'8';
+
+        let (_, message) = parse_message(&bytes).unwrap();
+        assert_eq!(validate_message(&message), vec![]);
+    }
+}
@@ -0,0 +1,575 @@
+//! IEEE 802.11 frames carrying Open Drone ID: Wi-Fi Beacon vendor specific IEs and Wi-Fi
+//! NAN service discovery frames. Frames are expected without a radiotap or other capture
+//! header.
+
+use nom::bytes::complete::take;
+use nom::number::complete::{le_u16, le_u8};
+use nom::IResult;
+
+use super::{parse_with_layer, ParseError, ParseErrorReason, ParseLayer, ParseResult};
+
+pub const WIFI_ALLIANCE_OUI: [u8; 3] = [0x50, 0x6f, 0x9a];
+pub const ASDSTAN_OUI: [u8; 3] = [0xfa, 0x0b, 0xbc];
+/// The first 6 bytes of the SHA-256 hash of the ODID service name, "org.opendroneid.remoteid".
+pub const NAN_SERVICE_ID: [u8; 6] = [0x88, 0x69, 0x19, 0x9d, 0x92, 0x09];
+/// OUI type of NAN service discovery frames and NAN beacon IEs, under `WIFI_ALLIANCE_OUI`.
+pub const NAN_OUI_TYPE: u8 = 0x13;
+pub const PUBLIC_ACTION_CATEGORY: u8 = 0x04;
+pub const VENDOR_SPECIFIC_PUBLIC_ACTION: u8 = 0x09;
+pub const NAN_SERVICE_DESCRIPTOR_ATTRIBUTE_ID: u8 = 0x03;
+
+const NAN_ATTRIBUTE_HEADER_LENGTH: usize = 3;
+// where the category follows the 802.11 header of an action frame
+const ACTION_FRAME_CATEGORY_OFFSET: usize = 24;
+
+// optional fields of a service descriptor attribute, in the order they appear
+const SERVICE_CONTROL_BINDING_BITMAP: u8 = 0x40;
+const SERVICE_CONTROL_MATCHING_FILTER: u8 = 0x04;
+const SERVICE_CONTROL_RESPONSE_FILTER: u8 = 0x08;
+const SERVICE_CONTROL_SERVICE_INFO: u8 = 0x10;
+
+#[derive(Debug)]
+pub struct WifiActionFrame<'a> {
+    pub frame_control: u16,
+    pub frame_control_version: u8, // first 2 bits, 000000xx
+    pub frame_control_type: u8,    // next 2 bits, 0000xx00
+    pub frame_control_subtype: u8, // next 4 bits, xxxx0000
+    pub duration_id: u16,
+    pub address1: &'a [u8],
+    pub address2: &'a [u8],
+    pub address3: &'a [u8],
+    pub sequence_control: u16,
+    pub category: u8,
+    pub action: u8,
+    pub oui: [u8; 3], // wi-fi alliance, 0x50, 0x6f, 0x9a
+    pub oui_type: u8,
+    pub body: &'a [u8],
+}
+
+#[derive(Debug)]
+pub struct WifiBeaconFrame<'a> {
+    pub frame_control: u16,
+    pub duration: u16,
+    pub destination_address: [u8; 6],
+    pub source_address: [u8; 6],
+    pub bssid: [u8; 6],
+    pub sequence_control: u16,
+    pub vendor_specific_data: &'a [u8],
+}
+
+/// One attribute of a NAN frame or NAN beacon IE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NanAttribute<'a> {
+    pub attribute_id: u8,
+    pub body: &'a [u8],
+}
+
+#[derive(Debug)]
+pub struct WifiServiceDescriptorAttribute<'a> {
+    pub attribute_id: u8,
+    pub attribute_length: u16,
+    pub service_id: &'a [u8],
+    pub instance_id: u8,
+    pub requestor_id: u8,
+    pub service_control: u8,
+    pub service_info_length: u8,
+    pub service_info: &'a [u8],
+    pub message_counter: u8,
+}
+
+// Function to check if a frame is a beacon frame
+pub fn is_beacon_frame(input: &[u8], header_length: u8) -> bool {
+    input.get(header_length as usize) == Some(&0x80)
+}
+
+pub fn is_action_frame(input: &[u8], header_length: u8) -> bool {
+    input.get(header_length as usize) == Some(&0xd0)
+}
+
+// takes N bytes into an array, failing like `take` on short input
+fn take_array<const N: usize>(input: &[u8]) -> IResult<&[u8], [u8; N]> {
+    let (input, bytes) = take(N)(input)?;
+    let mut array = [0u8; N];
+    array.copy_from_slice(bytes);
+
+    Ok((input, array))
+}
+
+// length and OUI of a vendor specific IE, following its tag number
+fn vendor_ie_header(input: &[u8]) -> IResult<&[u8], (u8, [u8; 3])> {
+    let (input, tag_length) = le_u8(input)?;
+    let (input, tag_oui) = take_array::<3>(input)?;
+
+    Ok((input, (tag_length, tag_oui)))
+}
+
+pub fn parse_nan_attribute(input: &[u8]) -> ParseResult<'_, NanAttribute<'_>> {
+    parse_with_layer(ParseLayer::NanServiceDescriptor, input, |input| {
+        let (input, attribute_id) = le_u8(input)?;
+        let (input, attribute_length) = le_u16(input)?;
+        let (input, body) = take(attribute_length)(input)?;
+
+        Ok((input, NanAttribute { attribute_id, body }))
+    })
+}
+
+pub fn parse_service_descriptor_attribute(
+    input: &[u8],
+) -> ParseResult<'_, WifiServiceDescriptorAttribute<'_>> {
+    let (rest, attribute) = parse_nan_attribute(input)?;
+
+    if attribute.attribute_id != NAN_SERVICE_DESCRIPTOR_ATTRIBUTE_ID {
+        return Err(ParseError::new(
+            ParseLayer::NanServiceDescriptor,
+            0,
+            ParseErrorReason::UnexpectedType,
+        ));
+    }
+
+    let attribute_length = attribute.body.len() as u16;
+
+    let (_, service_descriptor_attribute) =
+        parse_with_layer(ParseLayer::NanServiceDescriptor, attribute.body, |input| {
+            let (input, service_id) = take(6usize)(input)?;
+            let (input, instance_id) = le_u8(input)?;
+            let (input, requestor_id) = le_u8(input)?;
+            let (mut input, service_control) = le_u8(input)?;
+
+            if service_control & SERVICE_CONTROL_BINDING_BITMAP != 0 {
+                (input, _) = le_u16(input)?;
+            }
+
+            for filter in [
+                SERVICE_CONTROL_MATCHING_FILTER,
+                SERVICE_CONTROL_RESPONSE_FILTER,
+            ] {
+                if service_control & filter != 0 {
+                    let (rest, filter_length) = le_u8(input)?;
+                    (input, _) = take(filter_length)(rest)?;
+                }
+            }
+
+            let mut service_info_length = 0;
+            let mut message_counter = 0;
+            let mut service_info: &[u8] = &[];
+
+            // ODID starts the service info with a message counter
+            if service_control & SERVICE_CONTROL_SERVICE_INFO != 0 {
+                (input, service_info_length) = le_u8(input)?;
+
+                if service_info_length > 0 {
+                    (input, message_counter) = le_u8(input)?;
+                    (input, service_info) = take(service_info_length - 1)(input)?;
+                }
+            }
+
+            Ok((
+                input,
+                WifiServiceDescriptorAttribute {
+                    attribute_id: attribute.attribute_id,
+                    attribute_length,
+                    service_id,
+                    instance_id,
+                    requestor_id,
+                    service_control,
+                    service_info_length,
+                    message_counter,
+                    service_info,
+                },
+            ))
+        })
+        .map_err(|error| error.shifted(NAN_ATTRIBUTE_HEADER_LENGTH))?;
+
+    Ok((rest, service_descriptor_attribute))
+}
+
+/// The ODID service descriptor among NAN attributes, matched by its service ID. Attributes
+/// are walked only until it is found, so trailing bytes such as an FCS are ignored.
+pub fn parse_odid_service_descriptor(
+    input: &[u8],
+) -> ParseResult<'_, WifiServiceDescriptorAttribute<'_>> {
+    let mut offset = 0;
+
+    while offset < input.len() {
+        let (rest, attribute) =
+            parse_nan_attribute(&input[offset..]).map_err(|error| error.shifted(offset))?;
+
+        // other services' descriptors are skipped unparsed, malformed or not
+        if attribute.attribute_id == NAN_SERVICE_DESCRIPTOR_ATTRIBUTE_ID
+            && attribute.body.starts_with(&NAN_SERVICE_ID)
+        {
+            let end = offset + NAN_ATTRIBUTE_HEADER_LENGTH + attribute.body.len();
+            let (_, service_descriptor_attribute) =
+                parse_service_descriptor_attribute(&input[offset..end])
+                    .map_err(|error| error.shifted(offset))?;
+
+            return Ok((rest, service_descriptor_attribute));
+        }
+
+        offset = input.len() - rest.len();
+    }
+
+    Err(ParseError::new(
+        ParseLayer::NanServiceDescriptor,
+        input.len(),
+        ParseErrorReason::UnexpectedType,
+    ))
+}
+
+/// The ODID service descriptor of a NAN service discovery frame. Other public action frames
+/// and NAN frames for other services are rejected.
+pub fn parse_nan_action_frame(input: &[u8]) -> ParseResult<'_, WifiServiceDescriptorAttribute<'_>> {
+    let (_, action_frame) = parse_action_frame(input)?;
+
+    if action_frame.category != PUBLIC_ACTION_CATEGORY
+        || action_frame.action != VENDOR_SPECIFIC_PUBLIC_ACTION
+        || action_frame.oui != WIFI_ALLIANCE_OUI
+        || action_frame.oui_type != NAN_OUI_TYPE
+    {
+        return Err(ParseError::new(
+            ParseLayer::Ieee80211,
+            ACTION_FRAME_CATEGORY_OFFSET,
+            ParseErrorReason::UnexpectedType,
+        ));
+    }
+
+    let body_offset = input.len() - action_frame.body.len();
+
+    parse_odid_service_descriptor(action_frame.body).map_err(|error| error.shifted(body_offset))
+}
+
+pub fn parse_action_frame(input: &[u8]) -> ParseResult<'_, WifiActionFrame<'_>> {
+    parse_with_layer(ParseLayer::Ieee80211, input, |input| {
+        let (input, frame_control) = le_u16(input)?;
+        let frame_control_version = (frame_control & 0b00000011) as u8;
+        let frame_control_type = ((frame_control & 0b00001100) >> 2) as u8;
+        let frame_control_subtype = ((frame_control & 0b11110000) >> 4) as u8;
+        let (input, duration_id) = le_u16(input)?;
+        let (input, address1) = take(6usize)(input)?;
+        let (input, address2) = take(6usize)(input)?;
+        let (input, address3) = take(6usize)(input)?;
+        let (input, sequence_control) = le_u16(input)?;
+        let (input, category) = take(1usize)(input)?;
+        let (input, action) = take(1usize)(input)?;
+        let (input, oui) = take_array::<3>(input)?;
+        let (input, oui_type) = take(1usize)(input)?;
+        let (input, body) = take(input.len())(input)?;
+
+        Ok((
+            input,
+            WifiActionFrame {
+                frame_control,
+                frame_control_version,
+                frame_control_type,
+                frame_control_subtype,
+                duration_id,
+                address1,
+                address2,
+                address3,
+                sequence_control,
+                category: category[0],
+                action: action[0],
+                oui,
+                oui_type: oui_type[0],
+                body,
+            },
+        ))
+    })
+}
+
+pub fn parse_beacon_frame(input: &[u8]) -> ParseResult<'_, WifiBeaconFrame<'_>> {
+    let (
+        tagged_parameters,
+        (frame_control, duration, destination_addr, source_addr, bssid, sequence_control),
+    ) = parse_with_layer(ParseLayer::Ieee80211, input, |input| {
+        let (input, frame_control) = le_u16(input)?;
+        let (input, duration) = le_u16(input)?;
+        let (input, destination_addr) = take_array::<6>(input)?;
+        let (input, source_addr) = take_array::<6>(input)?;
+        let (input, bssid) = take_array::<6>(input)?;
+        let (input, sequence_control) = le_u16(input)?;
+        let (input, _fixed_parameters) = take(12usize)(input)?;
+
+        Ok((
+            input,
+            (
+                frame_control,
+                duration,
+                destination_addr,
+                source_addr,
+                bssid,
+                sequence_control,
+            ),
+        ))
+    })?;
+
+    let header_length = input.len() - tagged_parameters.len();
+
+    let (tagged_parameters, vendor_specific_data) =
+        parse_with_layer(ParseLayer::BeaconVendorIe, tagged_parameters, |input| {
+            let mut tagged_parameters = input;
+            let mut vendor_specific_data: &[u8] = &[];
+
+            // some transmitters miscount the length of the IEs before the vendor specific
+            // one, so the vendor IE is searched for at every offset instead
+            while !tagged_parameters.is_empty() {
+                let (new_input, tag_number) = le_u8(tagged_parameters)?;
+
+                tagged_parameters = new_input;
+
+                if tag_number != 0xdd {
+                    continue;
+                }
+
+                let (ie_input, (tag_length, tag_oui)) = match vendor_ie_header(new_input) {
+                    Ok(header) => header,
+                    Err(_) => continue,
+                };
+
+                if tag_oui != WIFI_ALLIANCE_OUI && tag_oui != ASDSTAN_OUI {
+                    continue;
+                }
+
+                // a NAN synchronization or discovery beacon, which can carry ODID in a
+                // service descriptor attribute
+                if tag_oui == WIFI_ALLIANCE_OUI && ie_input.first() == Some(&NAN_OUI_TYPE) {
+                    let attributes_length = (tag_length as usize).saturating_sub(4);
+
+                    let Some(attributes) = ie_input.get(1..1 + attributes_length) else {
+                        continue;
+                    };
+
+                    if let Ok((_, attribute)) = parse_odid_service_descriptor(attributes) {
+                        vendor_specific_data = attribute.service_info;
+                        tagged_parameters = &ie_input[1 + attributes_length..];
+                        break;
+                    }
+
+                    continue;
+                }
+
+                // the length also counts the OUI, OUI type and message counter
+                let message_pack_length = match (tag_length as usize).checked_sub(5) {
+                    Some(length) => length,
+                    None => {
+                        return Err(nom::Err::Failure(nom::error::Error::new(
+                            new_input,
+                            nom::error::ErrorKind::LengthValue,
+                        )))
+                    }
+                };
+
+                let (ie_input, _tag_oui_type) = le_u8(ie_input)?;
+                let (ie_input, _message_counter) = le_u8(ie_input)?;
+                let (ie_input, tag_data) = take(message_pack_length)(ie_input)?;
+
+                vendor_specific_data = tag_data;
+                tagged_parameters = ie_input;
+                break;
+            }
+
+            Ok((tagged_parameters, vendor_specific_data))
+        })
+        .map_err(|error| error.shifted(header_length))?;
+
+    Ok((
+        tagged_parameters,
+        WifiBeaconFrame {
+            frame_control,
+            duration,
+            destination_address: destination_addr,
+            source_address: source_addr,
+            bssid,
+            sequence_control,
+            vendor_specific_data,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_fixture;
+    use crate::{parse_message_pack, OdidMessage};
+
+    // the fixtures are radiotap captures, the header length is at bytes 2 and 3
+    fn read_frame_fixture(file_path: &str) -> Vec<u8> {
+        let bytes = read_fixture(file_path).unwrap();
+        let radiotap_length = u16::from_le_bytes([bytes[2], bytes[3]]) as usize;
+
+        bytes[radiotap_length..].to_vec()
+    }
+
+    #[test]
+    fn test_parse_beacon_frame_vendor_ie() {
+        let frame = read_frame_fixture("../fixtures/wlan_beacon_packet_data.txt");
+
+        assert!(is_beacon_frame(&frame, 0));
+        assert!(!is_action_frame(&frame, 0));
+
+        let (_, beacon_frame) = parse_beacon_frame(&frame).unwrap();
+        let (_, message_pack) = parse_message_pack(beacon_frame.vendor_specific_data).unwrap();
+
+        assert_eq!(message_pack.num_messages, 4);
+        assert!(matches!(message_pack.messages[0], OdidMessage::BasicId(_)));
+    }
+
+    #[test]
+    fn test_parse_service_descriptor_attribute() {
+        let frame = read_frame_fixture("../fixtures/wifi_packet_data.txt");
+
+        assert!(is_action_frame(&frame, 0));
+
+        let (_, action_frame) = parse_action_frame(&frame).unwrap();
+        let (_, attribute) = parse_service_descriptor_attribute(action_frame.body).unwrap();
+
+        assert_eq!(attribute.service_id, NAN_SERVICE_ID);
+        assert!(parse_message_pack(attribute.service_info).is_ok());
+    }
+
+    fn service_descriptor(service_id: [u8; 6], service_control: u8, fields: &[u8]) -> Vec<u8> {
+        let mut body = service_id.to_vec();
+        body.extend([0x01, 0x00, service_control]);
+        body.extend(fields);
+
+        let mut attribute = vec![NAN_SERVICE_DESCRIPTOR_ATTRIBUTE_ID];
+        attribute.extend((body.len() as u16).to_le_bytes());
+        attribute.extend(body);
+        attribute
+    }
+
+    #[test]
+    fn test_walk_nan_attributes() {
+        let frame = read_frame_fixture("../fixtures/wifi_packet_data.txt");
+        let (_, action_frame) = parse_action_frame(&frame).unwrap();
+        let (_, odid) = parse_service_descriptor_attribute(action_frame.body).unwrap();
+
+        // a device capability attribute and another service come before the ODID service
+        let mut attributes = vec![0x0f, 0x02, 0x00, 0xaa, 0xbb];
+        attributes.extend(service_descriptor([0x11; 6], 0x10, &[0x01, 0x00]));
+        attributes.extend(&action_frame.body[..3 + odid.attribute_length as usize]);
+
+        let (rest, attribute) = parse_odid_service_descriptor(&attributes).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(attribute.service_id, NAN_SERVICE_ID);
+        assert_eq!(attribute.service_info, odid.service_info);
+
+        let error = parse_odid_service_descriptor(&attributes[..attributes.len() - 1]).unwrap_err();
+        assert_eq!(error.layer, ParseLayer::NanServiceDescriptor);
+        assert_eq!(error.offset, 22);
+
+        let error = parse_odid_service_descriptor(&attributes[..19]).unwrap_err();
+        assert_eq!(error.reason, ParseErrorReason::UnexpectedType);
+    }
+
+    #[test]
+    fn test_skip_malformed_service_descriptors() {
+        let odid = service_descriptor(NAN_SERVICE_ID, 0x10, &[0x03, 0x07, 0xdd, 0xee]);
+
+        // another service announcing service info it doesn't carry, then one too short for
+        // a service ID
+        let mut attributes = service_descriptor([0x22; 6], 0x10, &[]);
+        attributes.extend([NAN_SERVICE_DESCRIPTOR_ATTRIBUTE_ID, 0x02, 0x00, 0x11, 0x22]);
+        attributes.extend(&odid);
+
+        let (rest, attribute) = parse_odid_service_descriptor(&attributes).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(attribute.service_info, &[0xdd, 0xee]);
+
+        // a malformed ODID descriptor is still reported, where its service info is missing
+        let mut attributes = service_descriptor([0x22; 6], 0x10, &[]);
+        let odid_offset = attributes.len();
+        attributes.extend(service_descriptor(NAN_SERVICE_ID, 0x10, &[]));
+        attributes.extend(&odid);
+
+        let error = parse_odid_service_descriptor(&attributes).unwrap_err();
+        assert_eq!(error.layer, ParseLayer::NanServiceDescriptor);
+        assert_eq!(error.offset, odid_offset + 12);
+    }
+
+    #[test]
+    fn test_service_control_fields() {
+        // binding bitmap, matching filter, service response filter, then the service info
+        let fields = [
+            0x01, 0x00, 0x02, 0xaa, 0xbb, 0x01, 0xcc, 0x03, 0x07, 0xdd, 0xee,
+        ];
+        let attribute = service_descriptor(NAN_SERVICE_ID, 0x5c, &fields);
+
+        let (rest, attribute) = parse_service_descriptor_attribute(&attribute).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(attribute.service_info_length, 3);
+        assert_eq!(attribute.message_counter, 0x07);
+        assert_eq!(attribute.service_info, &[0xdd, 0xee]);
+
+        // a publish without service info
+        let attribute = service_descriptor(NAN_SERVICE_ID, 0x00, &[]);
+        let (_, attribute) = parse_service_descriptor_attribute(&attribute).unwrap();
+        assert!(attribute.service_info.is_empty());
+
+        let error = parse_service_descriptor_attribute(&[0x0f, 0x00, 0x00]).unwrap_err();
+        assert_eq!(error.reason, ParseErrorReason::UnexpectedType);
+    }
+
+    #[test]
+    fn test_parse_nan_action_frame() {
+        let frame = read_frame_fixture("../fixtures/wifi_packet_data.txt");
+
+        // the fixture still ends with its FCS, which the walk never reaches
+        let (_, attribute) = parse_nan_action_frame(&frame).unwrap();
+        assert_eq!(attribute.service_id, NAN_SERVICE_ID);
+        assert!(parse_message_pack(attribute.service_info).is_ok());
+
+        // a public action frame that isn't NAN
+        let mut other = frame.clone();
+        other[24 + 5] = 0x10;
+        let error = parse_nan_action_frame(&other).unwrap_err();
+        assert_eq!(error.layer, ParseLayer::Ieee80211);
+        assert_eq!(error.offset, 24);
+        assert_eq!(error.reason, ParseErrorReason::UnexpectedType);
+
+        // a NAN frame for another service
+        let mut other = frame.clone();
+        other[24 + 9] ^= 0xff;
+        let error = parse_nan_action_frame(&other).unwrap_err();
+        assert_eq!(error.layer, ParseLayer::NanServiceDescriptor);
+    }
+
+    #[test]
+    fn test_parse_nan_beacon() {
+        let frame = read_frame_fixture("../fixtures/wifi_packet_data.txt");
+        let (_, action_frame) = parse_action_frame(&frame).unwrap();
+        let (_, odid) = parse_service_descriptor_attribute(action_frame.body).unwrap();
+
+        // master indication and cluster attributes, then the ODID service
+        let mut attributes = vec![0x00, 0x02, 0x00, 0x80, 0x01];
+        attributes.extend([0x01, 0x0d, 0x00]);
+        attributes.extend([0u8; 13]);
+        let sync_attributes = attributes.clone();
+        attributes.extend(&action_frame.body[..3 + odid.attribute_length as usize]);
+
+        let beacon = |attributes: &[u8]| {
+            let mut beacon = vec![0x80, 0x00];
+            beacon.extend([0u8; 22 + 12]);
+            beacon.extend([0xdd, (attributes.len() + 4) as u8]);
+            beacon.extend(WIFI_ALLIANCE_OUI);
+            beacon.push(NAN_OUI_TYPE);
+            beacon.extend(attributes);
+            beacon
+        };
+
+        let frame = beacon(&attributes);
+        let (_, beacon_frame) = parse_beacon_frame(&frame).unwrap();
+        assert_eq!(beacon_frame.vendor_specific_data, odid.service_info);
+
+        // a synchronization beacon without ODID
+        let frame = beacon(&sync_attributes);
+        let (_, beacon_frame) = parse_beacon_frame(&frame).unwrap();
+        assert!(beacon_frame.vendor_specific_data.is_empty());
+    }
+
+    #[test]
+    fn test_frame_type_out_of_bounds() {
+        assert!(!is_beacon_frame(&[], 0));
+        assert!(!is_action_frame(&[0xd0], 1));
+    }
+}
@@ -1,8 +1,11 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     bluetooth::BluetoothConfig, miner::config::MinerConfig, mqtt_client::MqttClientConfig,
-    web::WebConfig, wifi::WifiConfig,
+    odid::ManufacturerRegistry, web::WebConfig, wifi::WifiConfig,
 };
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -17,4 +20,24 @@ pub struct AppConfig {
     pub mqtt: MqttClientConfig,
     #[serde(default)]
     pub miner: MinerConfig,
+    /// A `code,manufacturer` CSV adding to the bundled manufacturer codes, its names taking
+    /// precedence for codes both list.
+    #[serde(default)]
+    pub manufacturer_codes: Option<PathBuf>,
+}
+
+impl AppConfig {
+    /// The bundled manufacturer codes, extended with `manufacturer_codes` if set.
+    pub fn manufacturer_registry(&self) -> anyhow::Result<ManufacturerRegistry> {
+        let mut registry = ManufacturerRegistry::bundled().clone();
+
+        if let Some(path) = &self.manufacturer_codes {
+            let csv =
+                std::fs::read_to_string(path).context("Couldn't read manufacturer codes file")?;
+
+            registry.extend(ManufacturerRegistry::from_csv(&csv));
+        }
+
+        Ok(registry)
+    }
 }
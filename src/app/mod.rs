@@ -8,7 +8,7 @@ pub use config::*;
 use sqlx::{Pool, Postgres};
 use tokio::sync::Mutex;
 
-use crate::{drone::Drone, mqtt_client::MqttClient};
+use crate::{drone::Drone, mqtt_client::MqttClient, odid::ManufacturerRegistry};
 
 use self::error::ApplicationError;
 
@@ -23,6 +23,10 @@ pub struct TrebuchetApp {
 
 impl TrebuchetApp {
     pub async fn init(pool: Pool<Postgres>, config: AppConfig) -> anyhow::Result<Self> {
+        if ManufacturerRegistry::install(config.manufacturer_registry()?).is_err() {
+            anyhow::bail!("Manufacturer codes were already loaded");
+        }
+
         let mqtt_client = MqttClient::init(config.mqtt.clone()).await?;
         Ok(Self {
             _config: config,
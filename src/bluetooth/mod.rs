@@ -1,9 +1,7 @@
 pub mod config;
-mod entity;
-mod repo;
 mod task;
 
 pub use config::*;
-pub use entity::*;
-pub use repo::*;
 pub use task::*;
+
+pub use crate::odid::bluetooth::*;
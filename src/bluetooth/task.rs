@@ -10,8 +10,8 @@ use super::parse_bluetooth_advertisement_frame;
 use crate::{
     drone::{Drone, DroneBuilder},
     odid::{
-        parse_basic_id, parse_location, parse_message_type, parse_operator_id,
-        parse_system_message, RemoteIdMessage,
+        parse_message, parse_message_pack, parse_message_type, parse_message_version,
+        RemoteIdMessage,
     },
     web::{insert_drone, DroneDto, DroneUpdate},
 };
@@ -103,48 +103,32 @@ pub async fn handle_bluetooth_event(
                     if let Ok((_, bt_advertisement_frame)) =
                         parse_bluetooth_advertisement_frame(data)
                     {
-                        match parse_message_type(&bt_advertisement_frame.message) {
-                            Ok((_, message_type)) => match message_type {
-                                RemoteIdMessage::SystemMessage => {
-                                    if let Ok((_, system_message)) =
-                                        parse_system_message(&bt_advertisement_frame.message)
-                                    {
-                                        let drone = drones.get_mut(&id.to_string()).unwrap();
-
-                                        drone.update_system_message(system_message);
-                                    }
-                                }
-                                RemoteIdMessage::BasicId => {
-                                    if let Ok((_, basic_id)) =
-                                        parse_basic_id(&bt_advertisement_frame.message)
-                                    {
-                                        let drone = drones.get_mut(&id.to_string()).unwrap();
-                                        drone.update_basic_id(basic_id);
-                                    }
-                                }
-                                RemoteIdMessage::Location => {
-                                    if let Ok((_, location)) =
-                                        parse_location(&bt_advertisement_frame.message)
-                                    {
-                                        let drone = drones.get_mut(&id.to_string()).unwrap();
-                                        drone.update_location(location);
-                                    }
-                                }
-                                RemoteIdMessage::OperatorId => {
-                                    if let Ok((_, operator)) =
-                                        parse_operator_id(&bt_advertisement_frame.message)
-                                    {
-                                        let drone = drones.get_mut(&id.to_string()).unwrap();
-                                        drone.update_operator(operator);
-                                    }
-                                }
-                                _ => {
-                                    return Some((id, 69));
+                        let message = bt_advertisement_frame.message;
+
+                        let messages = match parse_message_type(message) {
+                            Ok((_, RemoteIdMessage::MessagePack)) => {
+                                match parse_message_pack(message) {
+                                    Ok((_, message_pack)) => message_pack.messages.to_vec(),
+                                    Err(_) => return Some((id, 69)),
                                 }
+                            }
+                            Ok(_) => match parse_message(message) {
+                                Ok((_, message)) => vec![message],
+                                Err(_) => return Some((id, 69)),
                             },
                             Err(_) => {
                                 return Some((id, 69));
                             }
+                        };
+
+                        let drone = drones.get_mut(&id.to_string()).unwrap();
+
+                        if let Ok((_, version)) = parse_message_version(message) {
+                            drone.update_protocol_version(version);
+                        }
+
+                        for message in messages {
+                            drone.update_message(message);
                         }
                     }
 
@@ -1,7 +1,7 @@
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::app::AppConfig;
 
@@ -17,12 +17,16 @@ pub struct Config {
 
 pub struct EnvOverride {
     pub db_con: String,
+    pub replay_file: Option<PathBuf>,
 }
 
 impl Config {
     pub fn from_path(
         path: Option<impl AsRef<Path>>,
-        EnvOverride { db_con }: EnvOverride,
+        EnvOverride {
+            db_con,
+            replay_file,
+        }: EnvOverride,
     ) -> anyhow::Result<Self> {
         let mut config: Config = if let Some(path) = path {
             let config_file = std::fs::read_to_string(path).context("Couldn't read config file")?;
@@ -34,6 +38,10 @@ impl Config {
 
         config.db.pg_con = db_con;
 
+        if replay_file.is_some() {
+            config.app.wifi.replay_file = replay_file;
+        }
+
         Ok(config)
     }
 }
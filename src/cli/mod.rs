@@ -10,7 +10,7 @@ use crate::{
     app::TrebuchetApp,
     bluetooth::start_bluetooth_task,
     web::init_router,
-    wifi::{start_wifi_task, WifiInterface},
+    wifi::{start_wifi_replay_task, start_wifi_task, ChannelCoordinator},
 };
 
 use self::config::{Config, EnvOverride};
@@ -22,12 +22,21 @@ struct Cli {
     config: Option<PathBuf>,
     #[clap(env = "PG_CON")]
     pg_con: String,
+    /// Replay a .pcap/.pcapng capture instead of listening on the WiFi device
+    #[clap(long, env = "TREBUCHET_REPLAY", value_name = "FILE")]
+    replay: Option<PathBuf>,
 }
 
 pub async fn run() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let config = Config::from_path(cli.config, EnvOverride { db_con: cli.pg_con })?;
+    let config = Config::from_path(
+        cli.config,
+        EnvOverride {
+            db_con: cli.pg_con,
+            replay_file: cli.replay,
+        },
+    )?;
 
     run_cmd(config).await?;
 
@@ -47,7 +56,17 @@ async fn run_cmd(config: Config) -> anyhow::Result<()> {
     let pool = db::init_pool(&config.db).await?;
     let app = TrebuchetApp::init(pool.clone(), config.app.clone()).await?;
 
-    let (router, drone_update_tx) = init_router(pool.clone());
+    // Adapters are set up first, the web server reports on their channels
+    let coordinator = match config.app.wifi.replay_file {
+        Some(_) => None,
+        None => Some(ChannelCoordinator::init(&config.app.wifi).await?),
+    };
+    let wifi_interfaces = coordinator
+        .as_ref()
+        .map(|coordinator| coordinator.interfaces())
+        .unwrap_or_default();
+
+    let (router, drone_update_tx) = init_router(pool.clone(), wifi_interfaces.clone());
 
     let _ts_app = Arc::new(app.clone());
     let ts_pool = Arc::new(Mutex::new(pool.clone()));
@@ -71,42 +90,65 @@ async fn run_cmd(config: Config) -> anyhow::Result<()> {
     //    );
     //}));
 
-    let wifi_interface_send = send.clone();
-    println!("Starting WiFi interface modulator");
-    let wifi_interface_inner = WifiInterface::init(config.app.wifi.clone()).await?;
-    let wifi_interface = Arc::new(Mutex::new(wifi_interface_inner));
+    if let Some(replay_file) = config.app.wifi.replay_file.clone() {
+        println!("Starting WiFi replay");
+        let replay_send = send.clone();
+        let replay_pool = Arc::clone(&ts_pool);
+        let replay_drone_update = Arc::clone(&ts_drone_update);
+        let replay_drones = Arc::clone(&app.drones);
+        let replay_fcs_mode = config.app.wifi.fcs;
+        handles.push(tokio::spawn(async move {
+            let _ = replay_send.try_send(
+                start_wifi_replay_task(
+                    replay_file,
+                    replay_pool,
+                    replay_drones,
+                    replay_drone_update,
+                    replay_fcs_mode,
+                )
+                .await
+                .context("wifi replay task error"),
+            );
+        }));
+    }
+
+    if let Some(coordinator) = coordinator {
+        let wifi_interface_send = send.clone();
+        println!("Starting WiFi interface modulator");
 
-    // Spawn the modulator loop task using run_loop
-    handles.push(tokio::spawn({
-        let wifi_interface = Arc::clone(&wifi_interface);
-        async move {
+        // Spawn the modulator loop task, hopping every adapter
+        handles.push(tokio::spawn(async move {
             let _ = wifi_interface_send.try_send(
-                crate::wifi::WifiInterface::run_loop(wifi_interface)
+                coordinator
+                    .run_loop()
                     .await
                     .context("wifi interface task error"),
             );
+        }));
+
+        println!("Starting WiFi listeners");
+        // Every adapter feeds the same drone store
+        for wifi_interface in wifi_interfaces {
+            let wifi_send = send.clone();
+            let wifi_pool = Arc::clone(&ts_pool);
+            let wifi_drone_update = Arc::clone(&ts_drone_update);
+            let wifi_drones = Arc::clone(&app.drones);
+            let fcs_mode = config.app.wifi.fcs;
+            handles.push(tokio::spawn(async move {
+                let _ = wifi_send.try_send(
+                    start_wifi_task(
+                        wifi_pool,
+                        wifi_drones,
+                        wifi_drone_update,
+                        wifi_interface,
+                        fcs_mode,
+                    )
+                    .await
+                    .context("wifi task error"),
+                );
+            }));
         }
-    }));
-
-    println!("Starting WiFi listener");
-    let wifi_send = send.clone();
-    let wifi_pool = Arc::clone(&ts_pool);
-    let wifi_drone_update = Arc::clone(&ts_drone_update);
-    let wifi_drones = Arc::clone(&app.drones);
-    // Do not wrap wifi_interface again; simply clone it.
-    handles.push(tokio::spawn(async move {
-        let _ = wifi_send.try_send(
-            start_wifi_task(
-                config.app.wifi.device_name.clone(),
-                wifi_pool,
-                wifi_drones,
-                wifi_drone_update,
-                wifi_interface.clone(), // pass the shared instance directly
-            )
-            .await
-            .context("wifi task error"),
-        );
-    }));
+    }
 
     //println!("Starting miner to submit random payloads for testing purposes.");
     //let miner_send = send.clone();
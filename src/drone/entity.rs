@@ -1,7 +1,15 @@
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
-use crate::odid::{BasicId, Location, Operator, SystemMessage};
+use chrono::{DateTime, Utc};
+
+use crate::odid::{
+    validate_basic_id, validate_location, validate_operator, validate_self_id,
+    validate_system_message, Authentication, AuthenticationAssembler, AuthenticationBlob, BasicId,
+    Location, OdidMessage, Operator, ProtocolVersion, RemoteIdMessage, SelfId, SystemMessage,
+    Violation,
+};
+use crate::wifi::RadioMetadata;
 
 #[derive(Debug, Default, Builder, Serialize, Deserialize, Clone)]
 pub struct Drone {
@@ -10,15 +18,29 @@ pub struct Drone {
     #[builder(default = "0")]
     pub db_id: i32,
     #[builder(default = "None")]
+    pub protocol_version: Option<ProtocolVersion>,
+    #[builder(default = "None")]
     pub basic_id: Option<BasicId>,
     #[builder(default = "None")]
     pub last_location: Option<Location>,
+    #[builder(default = "None")]
+    pub last_location_received: Option<DateTime<Utc>>,
     #[builder(default = "vec![]")]
     pub location_history: Vec<Location>,
     #[builder(default = "None")]
     pub system_message: Option<SystemMessage>,
     #[builder(default = "None")]
     pub operator: Option<Operator>,
+    #[builder(default = "None")]
+    pub self_id: Option<SelfId>,
+    #[builder(default = "None")]
+    pub authentication: Option<AuthenticationBlob>,
+    /// How the latest WiFi frame from the drone was received.
+    #[builder(default = "None")]
+    pub radio: Option<RadioMetadata>,
+    #[serde(skip)]
+    #[builder(default = "AuthenticationAssembler::new()")]
+    pub authentication_assembler: AuthenticationAssembler,
 }
 
 impl Drone {
@@ -36,11 +58,17 @@ impl Drone {
         Drone {
             is_in_db: false,
             db_id: 0,
+            protocol_version: None,
             basic_id,
             last_location: last_location.clone(),
+            last_location_received: None,
             location_history: last_location_history,
             system_message,
             operator,
+            self_id: None,
+            authentication: None,
+            radio: None,
+            authentication_assembler: AuthenticationAssembler::new(),
         }
     }
 
@@ -49,6 +77,10 @@ impl Drone {
         self.db_id = db_id;
     }
 
+    pub fn update_protocol_version(&mut self, protocol_version: ProtocolVersion) {
+        self.protocol_version = Some(protocol_version);
+    }
+
     pub fn update_basic_id(&mut self, basic_id: BasicId) {
         self.basic_id = Some(basic_id);
     }
@@ -61,7 +93,29 @@ impl Drone {
         self.operator = Some(operator);
     }
 
+    pub fn update_self_id(&mut self, self_id: SelfId) {
+        self.self_id = Some(self_id);
+    }
+
+    pub fn update_radio(&mut self, radio: RadioMetadata) {
+        self.radio = Some(radio);
+    }
+
+    pub fn update_authentication(&mut self, page: Authentication) {
+        self.update_authentication_at(page, Utc::now());
+    }
+
+    pub fn update_authentication_at(&mut self, page: Authentication, received: DateTime<Utc>) {
+        if let Some(authentication) = self.authentication_assembler.push(page, received) {
+            self.authentication = Some(authentication);
+        }
+    }
+
     pub fn update_location(&mut self, location: Location) {
+        self.update_location_at(location, Utc::now());
+    }
+
+    pub fn update_location_at(&mut self, location: Location, received: DateTime<Utc>) {
         if let Some(last_location) = self.last_location.clone() {
             self.location_history.push(last_location.clone());
         } else {
@@ -69,6 +123,58 @@ impl Drone {
         }
 
         self.last_location = Some(location);
+        self.last_location_received = Some(received);
+    }
+
+    pub fn update_message(&mut self, message: OdidMessage) {
+        self.update_message_at(message, Utc::now());
+    }
+
+    /// Like `update_message`, with the time the message was received rather than the current
+    /// time, e.g. the timestamp of a replayed capture.
+    pub fn update_message_at(&mut self, message: OdidMessage, received: DateTime<Utc>) {
+        match message {
+            OdidMessage::BasicId(basic_id) => self.update_basic_id(basic_id),
+            OdidMessage::Location(location) => self.update_location_at(location, received),
+            OdidMessage::Authentication(page) => self.update_authentication_at(page, received),
+            OdidMessage::SelfId(self_id) => self.update_self_id(self_id),
+            OdidMessage::SystemMessage(system_message) => {
+                self.update_system_message(system_message)
+            }
+            OdidMessage::OperatorId(operator) => self.update_operator(operator),
+            OdidMessage::Unknown(_) => {}
+        }
+    }
+
+    /// Violations across the latest message of each type, plus any required message that
+    /// hasn't been received yet.
+    pub fn violations(&self) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        match &self.basic_id {
+            Some(basic_id) => violations.extend(validate_basic_id(basic_id)),
+            None => violations.push(Violation::MissingMessage(RemoteIdMessage::BasicId)),
+        }
+
+        match &self.last_location {
+            Some(location) => violations.extend(validate_location(location)),
+            None => violations.push(Violation::MissingMessage(RemoteIdMessage::Location)),
+        }
+
+        match &self.system_message {
+            Some(system_message) => violations.extend(validate_system_message(system_message)),
+            None => violations.push(Violation::MissingMessage(RemoteIdMessage::SystemMessage)),
+        }
+
+        if let Some(self_id) = &self.self_id {
+            violations.extend(validate_self_id(self_id));
+        }
+
+        if let Some(operator) = &self.operator {
+            violations.extend(validate_operator(operator));
+        }
+
+        violations
     }
 
     pub fn payload_ready(&self) -> bool {
@@ -93,6 +199,10 @@ impl Drone {
             println!("Operator Found");
             progress += 1;
         }
+        if self.self_id.is_some() {
+            println!("Self ID Found");
+            progress += 1;
+        }
         progress
     }
 }
@@ -5,6 +5,10 @@ pub mod cli;
 pub mod drone;
 pub mod miner;
 pub mod mqtt_client;
-pub mod odid;
 pub mod web;
 pub mod wifi;
+
+#[cfg(test)]
+mod test_util;
+
+pub use odid;
@@ -1,5 +0,0 @@
-mod entity;
-mod repo;
-
-pub use entity::*;
-pub use repo::*;
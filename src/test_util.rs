@@ -0,0 +1,22 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+/// Reads a fixture holding the bytes of a packet as a `[1, 2, ...]` list.
+pub fn read_fixture(file_path: &str) -> io::Result<Vec<u8>> {
+    // Open the file
+    let file = File::open(file_path)?;
+    let mut buf_reader = BufReader::new(file);
+
+    // Read the file content into a string
+    let mut content = String::new();
+    buf_reader.read_to_string(&mut content)?;
+
+    // Trim the square brackets and split the string by comma
+    let content = content.trim().trim_start_matches('[').trim_end_matches(']');
+    let bytes: Vec<u8> = content
+        .split(',')
+        .map(|s| s.trim().parse().expect("Failed to parse byte"))
+        .collect();
+
+    Ok(bytes)
+}
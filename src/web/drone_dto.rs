@@ -1,4 +1,5 @@
-use ::chrono::{DateTime, Utc};
+use ::chrono::{DateTime, Duration, Utc};
+use arrayvec::ArrayString;
 use fake::faker::address::en::{Latitude, Longitude};
 use fake::faker::boolean::en::*;
 use fake::faker::company::en::*;
@@ -11,6 +12,13 @@ use fake::{Dummy, Fake, Faker};
 use serde::{Deserialize, Serialize};
 
 use crate::drone::Drone;
+use crate::odid::{
+    decode_location_timestamp, parse_serial_number, validate_operator_id, ClassificationType,
+    DecodedLocation, DecodedSystemMessage, ManufacturerRegistry, OperatorIdStatus, SelfId,
+    SelfIdDescriptionType, SerialNumberError, SessionIdType, UaCategory, UaClass, UasIdType,
+    Violation,
+};
+use crate::wifi::RadioMetadata;
 
 #[derive(Clone, Serialize, Debug)]
 pub enum MutationKind {
@@ -29,6 +37,8 @@ pub struct DroneUpdate {
 pub struct DroneDto {
     pub id: i32,
     pub serial_number: String,
+    pub uas_id_type: Option<i16>,
+    pub session_id_type: Option<i16>,
     pub created: DateTime<Utc>,
     pub latitude: f64,
     pub longitude: f64,
@@ -40,12 +50,36 @@ pub struct DroneDto {
     pub pilot_longitude: f64,
     pub home_latitude: f64,
     pub home_longitude: f64,
+    pub self_id_type: Option<i16>,
+    pub self_id_description: Option<String>,
+    pub ua_classification_type: Option<i16>,
+    pub ua_category: Option<i16>,
+    pub ua_class: Option<i16>,
+    pub operator_altitude: Option<f64>,
+    pub system_timestamp: Option<DateTime<Utc>>,
+    pub operator_id_type: Option<i16>,
+    pub operator_id: Option<String>,
+    /// JSON array of `Violation`s found when the drone was last written.
+    pub violations: Option<String>,
+    /// Location timestamp placed in absolute UTC time, and when that location was received.
+    pub transmitted: Option<DateTime<Utc>>,
+    pub received: Option<DateTime<Utc>>,
+    pub timestamp_accuracy: Option<f64>,
+    /// `RadioMetadata` of the latest WiFi frame, RSSI and noise in dBm.
+    pub rssi: Option<i16>,
+    pub noise: Option<i16>,
+    pub frequency: Option<i32>,
+    pub radio_flags: Option<i16>,
+    pub antenna: Option<i16>,
+    pub tsft: Option<i64>,
 }
 
 impl DroneDto {
     pub fn dummy() -> Self {
         let id = Faker.fake::<i32>();
         let serial_number = CompanyName(EN).fake();
+        let uas_id_type = Some(u8::from(UasIdType::SerialNumber) as i16);
+        let session_id_type = None;
         let created = Faker.fake::<DateTime<Utc>>();
         let latitude: f64 = Latitude().fake();
         let longitude: f64 = Longitude().fake();
@@ -57,10 +91,31 @@ impl DroneDto {
         let pilot_longitude: f64 = Longitude().fake();
         let home_latitude: f64 = Latitude().fake();
         let home_longitude: f64 = Longitude().fake();
+        let self_id_type = Some(u8::from(SelfIdDescriptionType::Text) as i16);
+        let self_id_description: Option<String> = Some(Sentence(1..4).fake());
+        let ua_classification_type = Some(u8::from(ClassificationType::EuropeanUnion) as i16);
+        let ua_category = Some(u8::from(UaCategory::Open) as i16);
+        let ua_class = Some(u8::from(UaClass::Class1) as i16);
+        let operator_altitude = Some(Faker.fake::<f64>());
+        let system_timestamp = Some(Faker.fake::<DateTime<Utc>>());
+        let operator_id_type = Some(0);
+        let operator_id = Some("FIN87astrdge12k8".to_string());
+        let violations = Some("[]".to_string());
+        let received = Some(created);
+        let transmitted = Some(created - Duration::milliseconds((0..2000i64).fake()));
+        let timestamp_accuracy = Some(0.1);
+        let rssi = Some((-90..-30i16).fake());
+        let noise = None;
+        let frequency = Some(2437);
+        let radio_flags = Some(0x10);
+        let antenna = Some(0);
+        let tsft = None;
 
         DroneDto {
             id,
             serial_number,
+            uas_id_type,
+            session_id_type,
             created,
             latitude,
             longitude,
@@ -72,8 +127,41 @@ impl DroneDto {
             pilot_longitude,
             home_latitude,
             home_longitude,
+            self_id_type,
+            self_id_description,
+            ua_classification_type,
+            ua_category,
+            ua_class,
+            operator_altitude,
+            system_timestamp,
+            operator_id_type,
+            operator_id,
+            violations,
+            transmitted,
+            received,
+            timestamp_accuracy,
+            rssi,
+            noise,
+            frequency,
+            radio_flags,
+            antenna,
+            tsft,
         }
     }
+
+    fn radio(&self) -> Option<RadioMetadata> {
+        let radio = RadioMetadata {
+            signal: self.rssi.map(|rssi| rssi as i8),
+            noise: self.noise.map(|noise| noise as i8),
+            frequency: self.frequency.map(|frequency| frequency as u16),
+            flags: self.radio_flags.map(|flags| flags as u8),
+            antenna: self.antenna.map(|antenna| antenna as u8),
+            tsft: self.tsft.map(|tsft| tsft as u64),
+        };
+
+        // drones heard over Bluetooth have no radiotap header
+        (radio != RadioMetadata::default()).then_some(radio)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +173,10 @@ pub struct Position {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DroneSerialized {
     pub serial_number: String,
+    pub uas_id_type: Option<UasIdType>,
+    pub session_id_type: Option<SessionIdType>,
+    pub manufacturer: Option<String>,
+    pub serial_number_error: Option<SerialNumberError>,
     pub created: DateTime<Utc>,
     pub altitude: f64,
     pub x_speed: f64,
@@ -93,12 +185,68 @@ pub struct DroneSerialized {
     pub position: Position,
     pub pilot_position: Position,
     pub home_position: Position,
+    pub self_id: Option<SelfId>,
+    pub classification_type: Option<ClassificationType>,
+    pub ua_category: Option<UaCategory>,
+    pub ua_class: Option<UaClass>,
+    pub operator_altitude: Option<f64>,
+    pub system_timestamp: Option<DateTime<Utc>>,
+    pub operator_id: Option<String>,
+    /// `ChecksumUnverified` for well formed EU IDs, their secret characters are never
+    /// broadcast.
+    pub operator_id_status: Option<OperatorIdStatus>,
+    pub violations: Vec<Violation>,
+    pub transmitted: Option<DateTime<Utc>>,
+    pub received: Option<DateTime<Utc>>,
+    pub timestamp_accuracy: Option<f64>,
+    /// Milliseconds between transmission and reception, negative when the transmitter's
+    /// clock runs ahead of ours.
+    pub transmit_gap_ms: Option<i64>,
+    pub radio: Option<RadioMetadata>,
 }
 
 impl From<DroneDto> for DroneSerialized {
     fn from(drone_dto: DroneDto) -> Self {
+        let uas_id_type = drone_dto
+            .uas_id_type
+            .map(|uas_id_type| UasIdType::from(uas_id_type as u8));
+
+        // only serial numbers carry a manufacturer code
+        let serial_number = match uas_id_type {
+            Some(UasIdType::SerialNumber) => Some(parse_serial_number(&drone_dto.serial_number)),
+            _ => None,
+        };
+
+        let manufacturer = match &serial_number {
+            Some(Ok(serial_number)) => ManufacturerRegistry::current()
+                .lookup(&serial_number.manufacturer_code)
+                .map(|manufacturer| manufacturer.to_string()),
+            _ => None,
+        };
+        let serial_number_error = serial_number.and_then(|serial_number| serial_number.err());
+
+        let operator_id_status = drone_dto
+            .operator_id
+            .as_ref()
+            .map(|operator_id| validate_operator_id(operator_id, None));
+
+        let radio = drone_dto.radio();
+
+        let transmit_gap_ms = match (drone_dto.transmitted, drone_dto.received) {
+            (Some(transmitted), Some(received)) => {
+                Some((received - transmitted).num_milliseconds())
+            }
+            _ => None,
+        };
+
         DroneSerialized {
             serial_number: drone_dto.serial_number,
+            uas_id_type,
+            session_id_type: drone_dto
+                .session_id_type
+                .map(|session_id_type| SessionIdType::from(session_id_type as u8)),
+            manufacturer,
+            serial_number_error,
             created: drone_dto.created,
             altitude: drone_dto.altitude,
             x_speed: drone_dto.x_speed,
@@ -116,65 +264,120 @@ impl From<DroneDto> for DroneSerialized {
                 lat: drone_dto.home_latitude,
                 lng: drone_dto.home_longitude,
             },
+            self_id: drone_dto.self_id_description.map(|description| SelfId {
+                description_type: SelfIdDescriptionType::from(
+                    drone_dto.self_id_type.unwrap_or_default() as u8,
+                ),
+                // the column is as wide as the broadcast field
+                description: ArrayString::from(&description).unwrap_or_default(),
+            }),
+            classification_type: drone_dto
+                .ua_classification_type
+                .map(|classification_type| ClassificationType::from(classification_type as u8)),
+            ua_category: drone_dto
+                .ua_category
+                .map(|ua_category| UaCategory::from(ua_category as u8)),
+            ua_class: drone_dto
+                .ua_class
+                .map(|ua_class| UaClass::from(ua_class as u8)),
+            operator_altitude: drone_dto.operator_altitude,
+            system_timestamp: drone_dto.system_timestamp,
+            operator_id: drone_dto.operator_id,
+            operator_id_status,
+            violations: drone_dto
+                .violations
+                .and_then(|violations| serde_json::from_str(&violations).ok())
+                .unwrap_or_default(),
+            transmitted: drone_dto.transmitted,
+            received: drone_dto.received,
+            timestamp_accuracy: drone_dto.timestamp_accuracy,
+            transmit_gap_ms,
+            radio,
         }
     }
 }
 
 impl From<Drone> for DroneDto {
     fn from(drone: Drone) -> Self {
-        let latitude_int = drone.last_location.as_ref().unwrap().latitude_int;
-        let longitude_int = drone.last_location.as_ref().unwrap().longitude_int;
+        let violations = serde_json::to_string(&drone.violations()).ok();
 
-        let latitude: f64 = (latitude_int as f64 / 10_f64.powi(7)).into();
-        let longitude: f64 = (longitude_int as f64 / 10_f64.powi(7)).into();
+        let location = DecodedLocation::from(drone.last_location.as_ref().unwrap());
 
-        let altitude: f64 = drone.last_location.as_ref().unwrap().height.into();
-        let altitude: f64 = (altitude * 0.5) - 1000.0;
+        let latitude = location.latitude;
+        let longitude = location.longitude;
+        let altitude = location.altitude_geodetic.unwrap_or_default();
+        let yaw = location.track_direction.unwrap_or_default();
+        let speed = location.speed.unwrap_or_default();
+        let y_speed = location.vertical_speed.unwrap_or_default();
 
-        let ew_direction = drone.last_location.as_ref().unwrap().ew_direction;
-        let yaw: f64 = drone
-            .last_location
-            .as_ref()
-            .unwrap()
-            .tracking_direction
-            .into();
-        let yaw = if ew_direction == 1 { yaw + 180.0 } else { yaw };
-
-        let speed_multiplier = drone.last_location.as_ref().unwrap().speed_multiplier;
-        let speed: f64 = drone.last_location.as_ref().unwrap().speed.into();
-        let speed = if speed_multiplier == 1 {
-            speed * 0.25
-        } else {
-            if speed > 0.0 {
-                speed * 0.75 + 63.75
-            } else {
-                speed
-            }
-        };
+        let system_message = DecodedSystemMessage::from(drone.system_message.as_ref().unwrap());
 
-        let y_speed: f64 = drone.last_location.as_ref().unwrap().vertical_speed.into();
-        let y_speed = y_speed * 0.5;
+        let pilot_latitude = system_message.operator_latitude;
+        let pilot_longitude = system_message.operator_longitude;
 
-        let pilot_latitude_int = drone.system_message.as_ref().unwrap().operator_latitude_int;
-        let pilot_longitude_int = drone.system_message.unwrap().operator_longitude_int;
+        let ua_classification_type = Some(u8::from(system_message.classification_type) as i16);
+        let ua_category = Some(u8::from(system_message.ua_category) as i16);
+        let ua_class = Some(u8::from(system_message.ua_class) as i16);
+        let operator_altitude = system_message.operator_altitude_geodetic;
+        let system_timestamp = system_message.timestamp;
 
-        let pilot_latitude: f64 = (pilot_latitude_int as f64 / 10_f64.powi(7)).into();
-        let pilot_longitude: f64 = (pilot_longitude_int as f64 / 10_f64.powi(7)).into();
+        let home_location = DecodedLocation::from(drone.location_history.first().unwrap());
 
-        let drone_first_location = drone.location_history.first().unwrap();
+        let home_latitude = home_location.latitude;
+        let home_longitude = home_location.longitude;
 
-        let home_latitude_int = drone_first_location.latitude_int;
-        let home_longitude_int = drone_first_location.longitude_int;
+        let self_id_type = drone
+            .self_id
+            .as_ref()
+            .map(|self_id| u8::from(self_id.description_type.clone()) as i16);
+        let self_id_description = drone.self_id.map(|self_id| self_id.description.to_string());
 
-        let home_latitude: f64 = (home_latitude_int as f64 / 10_f64.powi(7)).into();
-        let home_longitude: f64 = (home_longitude_int as f64 / 10_f64.powi(7)).into();
+        let operator_id_type = drone
+            .operator
+            .as_ref()
+            .map(|operator| operator.operator_id_type as i16);
+        let operator_id = drone
+            .operator
+            .map(|operator| operator.operator_id.to_string());
+
+        let basic_id = drone.basic_id.unwrap();
+
+        let uas_id_type = Some(u8::from(basic_id.uas_id_type) as i16);
+        let session_id_type = basic_id
+            .session_id_type
+            .map(|session_id_type| u8::from(session_id_type) as i16);
 
         let id = if drone.is_in_db { drone.db_id } else { 0 };
 
-        let created: DateTime<Utc> = Utc::now();
+        // when the location was received, the capture's time for replays, so a replay isn't
+        // shown as live traffic
+        let created: DateTime<Utc> = drone.last_location_received.unwrap_or_else(Utc::now);
+
+        let transmitted = drone
+            .last_location
+            .as_ref()
+            .and_then(|location| decode_location_timestamp(location.timestamp, created));
+        let received = Some(created);
+        let timestamp_accuracy = location.timestamp_accuracy;
+
+        let radio = drone.radio.unwrap_or_default();
 
         DroneDto {
-            serial_number: drone.basic_id.unwrap().uas_id,
+            serial_number: basic_id.uas_id.to_string(),
+            uas_id_type,
+            session_id_type,
+            operator_id_type,
+            operator_id,
+            violations,
+            transmitted,
+            received,
+            timestamp_accuracy,
+            rssi: radio.signal.map(i16::from),
+            noise: radio.noise.map(i16::from),
+            frequency: radio.frequency.map(i32::from),
+            radio_flags: radio.flags.map(i16::from),
+            antenna: radio.antenna.map(i16::from),
+            tsft: radio.tsft.map(|tsft| tsft as i64),
             latitude,
             longitude,
             altitude,
@@ -185,6 +388,13 @@ impl From<Drone> for DroneDto {
             pilot_longitude,
             home_latitude,
             home_longitude,
+            self_id_type,
+            self_id_description,
+            ua_classification_type,
+            ua_category,
+            ua_class,
+            operator_altitude,
+            system_timestamp,
             id,
             created,
         }
@@ -3,24 +3,36 @@ use axum::{
     Extension, Router,
 };
 use sqlx::PgPool;
+use std::sync::Arc;
 
 // use crate::routes;
 
-use tokio::sync::broadcast::{channel, Sender};
+use tokio::sync::{
+    broadcast::{channel, Sender},
+    Mutex,
+};
 use tower_http::services::ServeDir;
 
 use super::{routes, DroneUpdate};
+use crate::wifi::WifiInterface;
 
 pub type DronesStream = Sender<DroneUpdate>;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
+    pub wifi_interfaces: Vec<Arc<Mutex<WifiInterface>>>,
 }
 
-pub fn init_router(db: PgPool) -> (Router, DronesStream) {
+pub fn init_router(
+    db: PgPool,
+    wifi_interfaces: Vec<Arc<Mutex<WifiInterface>>>,
+) -> (Router, DronesStream) {
     let (tx, _rx) = channel::<DroneUpdate>(10);
-    let state = AppState { db };
+    let state = AppState {
+        db,
+        wifi_interfaces,
+    };
 
     (
         Router::new()
@@ -29,6 +41,7 @@ pub fn init_router(db: PgPool) -> (Router, DronesStream) {
             .route("/api/drones/active", get(routes::get_active_drones))
             .route("/api/drones/all", get(routes::get_all_drones))
             .route("/api/stream", get(routes::handle_stream))
+            .route("/api/wifi/channels", get(routes::get_wifi_channels))
             .with_state(state)
             .layer(Extension(tx.clone())),
         tx,
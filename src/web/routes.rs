@@ -56,6 +56,29 @@ pub async fn get_all_drones(State(state): State<AppState>) -> Result<impl IntoRe
         .unwrap())
 }
 
+/// Per-channel ODID hits and listening time of every WiFi adapter.
+pub async fn get_wifi_channels(State(state): State<AppState>) -> impl IntoResponse {
+    let mut interfaces = vec![];
+
+    for wifi_interface in &state.wifi_interfaces {
+        let wifi_interface = wifi_interface.lock().await;
+
+        interfaces.push(json!({
+            "name": wifi_interface.name,
+            "channel": wifi_interface.channel,
+            "channels": wifi_interface.channels,
+            "strategy": wifi_interface.scheduler.strategy_name(),
+            "stats": wifi_interface.scheduler.stats(),
+        }));
+    }
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&interfaces).unwrap())
+        .unwrap()
+}
+
 pub async fn update_drone(drone: DroneDto, db: &sqlx::PgPool, tx: &DronesStream) {
     let drone_copy = drone.clone();
     let _ = sqlx::query(
@@ -70,8 +93,29 @@ pub async fn update_drone(drone: DroneDto, db: &sqlx::PgPool, tx: &DronesStream)
         pilot_latitude = $8,
         pilot_longitude = $9,
         home_latitude = $10,
-        home_longitude = $11
-    WHERE id = $12",
+        home_longitude = $11,
+        self_id_type = $12,
+        self_id_description = $13,
+        ua_classification_type = $14,
+        ua_category = $15,
+        ua_class = $16,
+        operator_altitude = $17,
+        system_timestamp = $18,
+        uas_id_type = $19,
+        session_id_type = $20,
+        operator_id_type = $21,
+        operator_id = $22,
+        violations = $23,
+        transmitted = $24,
+        received = $25,
+        timestamp_accuracy = $26,
+        rssi = $27,
+        noise = $28,
+        frequency = $29,
+        radio_flags = $30,
+        antenna = $31,
+        tsft = $32
+    WHERE id = $33",
     )
     .bind(drone.serial_number)
     .bind(drone.latitude)
@@ -84,6 +128,27 @@ pub async fn update_drone(drone: DroneDto, db: &sqlx::PgPool, tx: &DronesStream)
     .bind(drone.pilot_longitude)
     .bind(drone.home_latitude)
     .bind(drone.home_longitude)
+    .bind(drone.self_id_type)
+    .bind(drone.self_id_description)
+    .bind(drone.ua_classification_type)
+    .bind(drone.ua_category)
+    .bind(drone.ua_class)
+    .bind(drone.operator_altitude)
+    .bind(drone.system_timestamp)
+    .bind(drone.uas_id_type)
+    .bind(drone.session_id_type)
+    .bind(drone.operator_id_type)
+    .bind(drone.operator_id)
+    .bind(drone.violations)
+    .bind(drone.transmitted)
+    .bind(drone.received)
+    .bind(drone.timestamp_accuracy)
+    .bind(drone.rssi)
+    .bind(drone.noise)
+    .bind(drone.frequency)
+    .bind(drone.radio_flags)
+    .bind(drone.antenna)
+    .bind(drone.tsft)
     .bind(drone.id)
     .execute(db)
     .await
@@ -106,8 +171,17 @@ pub async fn insert_drone(drone: DroneDto, db: &sqlx::PgPool, tx: &DronesStream)
         yaw,
         x_speed, y_speed,
         pilot_latitude, pilot_longitude,
-        home_latitude, home_longitude
-    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING id, created, serial_number, latitude, longitude, altitude, yaw, x_speed, y_speed, pilot_latitude, pilot_longitude, home_latitude, home_longitude",
+        home_latitude, home_longitude,
+        self_id_type, self_id_description,
+        ua_classification_type, ua_category, ua_class,
+        operator_altitude, system_timestamp,
+        uas_id_type, session_id_type,
+        operator_id_type, operator_id,
+        violations,
+        transmitted, received, timestamp_accuracy,
+        rssi, noise, frequency, radio_flags, antenna, tsft,
+        created
+    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33) RETURNING *",
     )
     .bind(drone.serial_number)
     .bind(drone.latitude)
@@ -120,6 +194,28 @@ pub async fn insert_drone(drone: DroneDto, db: &sqlx::PgPool, tx: &DronesStream)
     .bind(drone.pilot_longitude)
     .bind(drone.home_latitude)
     .bind(drone.home_longitude)
+    .bind(drone.self_id_type)
+    .bind(drone.self_id_description)
+    .bind(drone.ua_classification_type)
+    .bind(drone.ua_category)
+    .bind(drone.ua_class)
+    .bind(drone.operator_altitude)
+    .bind(drone.system_timestamp)
+    .bind(drone.uas_id_type)
+    .bind(drone.session_id_type)
+    .bind(drone.operator_id_type)
+    .bind(drone.operator_id)
+    .bind(drone.violations)
+    .bind(drone.transmitted)
+    .bind(drone.received)
+    .bind(drone.timestamp_accuracy)
+    .bind(drone.rssi)
+    .bind(drone.noise)
+    .bind(drone.frequency)
+    .bind(drone.radio_flags)
+    .bind(drone.antenna)
+    .bind(drone.tsft)
+    .bind(drone.created)
     .fetch_one(db)
     .await
     .unwrap();
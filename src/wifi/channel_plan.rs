@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+
+use super::{channel_to_frequency, Channel, ChannelWidth, Nl80211Error};
+
+/// Regulatory domain a channel plan is drawn up for.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Region {
+    Us,
+    Eu,
+    Jp,
+}
+
+/// What Remote ID is expected on a planned channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelUse {
+    /// NAN discovery: channel 6 at 2.4 GHz, channel 149 at 5 GHz.
+    Nan,
+    Beacon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedChannel {
+    pub number: u64,
+    pub channel: Channel,
+    pub channel_use: ChannelUse,
+}
+
+impl PlannedChannel {
+    fn new(number: u64, channel_use: ChannelUse) -> Self {
+        let frequency = channel_to_frequency(number).expect("Planned channel without frequency");
+
+        PlannedChannel {
+            number,
+            channel: Channel::new(frequency),
+            channel_use,
+        }
+    }
+
+    pub fn frequency(&self) -> u32 {
+        self.channel.frequency
+    }
+
+    pub fn width(&self) -> ChannelWidth {
+        self.channel.width
+    }
+}
+
+/// The channels to listen for Remote ID on, in hopping order. Remote ID is sent on 20 MHz
+/// channels, NAN channels come first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelPlan {
+    pub region: Option<Region>,
+    pub channels: Vec<PlannedChannel>,
+}
+
+impl ChannelPlan {
+    // NAN discovery channels are in every plan, whether the adapter's regulatory domain lets
+    // it tune to them is checked with `supported`
+    const NAN_CHANNELS: [u64; 2] = [6, 149];
+
+    pub fn for_region(region: Region) -> Self {
+        let beacon_channels: &[u64] = match region {
+            Region::Us => &[1, 11, 36, 40, 44, 48, 153, 157, 161, 165],
+            Region::Eu => &[1, 11, 13, 36, 40, 44, 48, 153, 157, 161, 165],
+            Region::Jp => &[1, 11, 13, 36, 40, 44, 48],
+        };
+
+        let channels = ChannelPlan::NAN_CHANNELS
+            .iter()
+            .map(|number| PlannedChannel::new(*number, ChannelUse::Nan))
+            .chain(
+                beacon_channels
+                    .iter()
+                    .map(|number| PlannedChannel::new(*number, ChannelUse::Beacon)),
+            )
+            .collect();
+
+        ChannelPlan {
+            region: Some(region),
+            channels,
+        }
+    }
+
+    /// A plan of bare channel numbers, as `wifi.channels` lists them.
+    pub fn custom(numbers: &[u64]) -> Result<Self, Nl80211Error> {
+        let channels = numbers
+            .iter()
+            .map(|number| {
+                let frequency =
+                    channel_to_frequency(*number).ok_or(Nl80211Error::UnknownChannel(*number))?;
+
+                let channel_use = if ChannelPlan::NAN_CHANNELS.contains(number) {
+                    ChannelUse::Nan
+                } else {
+                    ChannelUse::Beacon
+                };
+
+                Ok(PlannedChannel {
+                    number: *number,
+                    channel: Channel::new(frequency),
+                    channel_use,
+                })
+            })
+            .collect::<Result<_, Nl80211Error>>()?;
+
+        Ok(ChannelPlan {
+            region: None,
+            channels,
+        })
+    }
+
+    pub fn numbers(&self) -> Vec<u64> {
+        self.channels.iter().map(|channel| channel.number).collect()
+    }
+
+    pub fn contains(&self, number: u64) -> bool {
+        self.channel(number).is_some()
+    }
+
+    pub fn channel(&self, number: u64) -> Option<&PlannedChannel> {
+        self.channels
+            .iter()
+            .find(|channel| channel.number == number)
+    }
+
+    /// The part of the plan an adapter can tune to, given the frequencies it reports.
+    pub fn supported(&self, frequencies: &[u32]) -> ChannelPlan {
+        ChannelPlan {
+            region: self.region,
+            channels: self
+                .channels
+                .iter()
+                .filter(|channel| frequencies.contains(&channel.frequency()))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// The part of the plan an adapter can't tune to.
+    pub fn unsupported(&self, frequencies: &[u32]) -> Vec<u64> {
+        self.channels
+            .iter()
+            .filter(|channel| !frequencies.contains(&channel.frequency()))
+            .map(|channel| channel.number)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelPlan, ChannelUse, ChannelWidth, Region};
+
+    #[test]
+    fn test_region_plans() {
+        for region in [Region::Us, Region::Eu, Region::Jp] {
+            let plan = ChannelPlan::for_region(region);
+
+            assert_eq!(plan.region, Some(region));
+            assert_eq!(&plan.numbers()[..2], &[6, 149]);
+            assert_eq!(plan.channel(6).unwrap().frequency(), 2437);
+            assert_eq!(plan.channel(149).unwrap().frequency(), 5745);
+            assert_eq!(plan.channel(149).unwrap().channel_use, ChannelUse::Nan);
+            assert!(plan.contains(1));
+            assert!(plan.contains(36));
+            assert!(plan
+                .channels
+                .iter()
+                .all(|channel| channel.width() == ChannelWidth::Mhz20NoHt));
+        }
+
+        assert!(!ChannelPlan::for_region(Region::Us).contains(13));
+        assert!(ChannelPlan::for_region(Region::Eu).contains(13));
+        assert!(!ChannelPlan::for_region(Region::Jp).contains(153));
+    }
+
+    #[test]
+    fn test_custom_plan() {
+        let plan = ChannelPlan::custom(&[1, 6, 11]).unwrap();
+
+        assert_eq!(plan.region, None);
+        assert_eq!(plan.numbers(), vec![1, 6, 11]);
+        assert_eq!(plan.channel(6).unwrap().channel_use, ChannelUse::Nan);
+        assert_eq!(plan.channel(11).unwrap().channel_use, ChannelUse::Beacon);
+
+        assert!(ChannelPlan::custom(&[1, 15]).is_err());
+    }
+
+    #[test]
+    fn test_supported_channels() {
+        let plan = ChannelPlan::for_region(Region::Us);
+        // a 2.4 GHz only adapter
+        let frequencies: Vec<u32> = (1..=11).map(|channel| 2407 + 5 * channel).collect();
+
+        assert_eq!(plan.supported(&frequencies).numbers(), vec![6, 1, 11]);
+        assert_eq!(
+            plan.unsupported(&frequencies),
+            vec![149, 36, 40, 44, 48, 153, 157, 161, 165]
+        );
+        assert!(plan.supported(&[]).channels.is_empty());
+    }
+}
@@ -1,21 +1,110 @@
+use std::path::PathBuf;
+
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
 
+use super::{
+    ChannelPlan, ExtendedDwell, FcsMode, HitWeighted, HoppingStrategy, Nl80211Error, Region,
+    RoundRobin,
+};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WifiConfig {
     #[serde(default)]
     pub device_name: String,
+    /// Adapters to capture on together, sharing `channels`. Takes precedence over
+    /// `device_name`.
+    #[serde(default)]
+    pub devices: Vec<String>,
     #[serde(default)]
     pub channels: Vec<u64>,
+    /// A regulatory channel plan to hop over instead of `channels`.
+    #[serde(default)]
+    pub channel_plan: Option<Region>,
     #[serde(default)]
     pub channel_mod_freq_ms: u64,
+    /// A `.pcap`/`.pcapng` capture to replay instead of listening on `device_name`.
+    #[serde(default)]
+    pub replay_file: Option<PathBuf>,
+    #[serde(default)]
+    pub hopping: HoppingConfig,
+    /// Whether frames failing their FCS check are dropped.
+    #[serde(default)]
+    pub fcs: FcsMode,
 }
 
 impl Default for WifiConfig {
     fn default() -> Self {
         WifiConfig {
             device_name: "".to_string(),
+            devices: vec![],
             channels: default_channels(),
+            channel_plan: None,
             channel_mod_freq_ms: default_channel_mod_freq_ms(),
+            replay_file: None,
+            hopping: HoppingConfig::default(),
+            fcs: FcsMode::default(),
+        }
+    }
+}
+
+impl WifiConfig {
+    pub fn device_names(&self) -> Vec<String> {
+        if self.devices.is_empty() {
+            vec![self.device_name.clone()]
+        } else {
+            self.devices.clone()
+        }
+    }
+
+    pub fn channel_plan(&self) -> Result<ChannelPlan, Nl80211Error> {
+        match self.channel_plan {
+            Some(region) => Ok(ChannelPlan::for_region(region)),
+            None => ChannelPlan::custom(&self.channels),
+        }
+    }
+}
+
+/// How adapters hop between their channels, each channel being listened to for at least
+/// `channel_mod_freq_ms`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum HoppingConfig {
+    RoundRobin,
+    HitWeighted,
+    ExtendedDwell {
+        #[serde(default = "default_extension_ms")]
+        extension_ms: u64,
+        #[serde(default)]
+        max_dwell_ms: Option<u64>,
+    },
+}
+
+impl Default for HoppingConfig {
+    fn default() -> Self {
+        HoppingConfig::ExtendedDwell {
+            extension_ms: default_extension_ms(),
+            max_dwell_ms: None,
+        }
+    }
+}
+
+impl HoppingConfig {
+    pub fn strategy(&self, dwell_ms: u64) -> Box<dyn HoppingStrategy> {
+        let dwell = Duration::milliseconds(dwell_ms as i64);
+
+        match self {
+            HoppingConfig::RoundRobin => Box::new(RoundRobin { dwell }),
+            HoppingConfig::HitWeighted => Box::new(HitWeighted::new(dwell)),
+            HoppingConfig::ExtendedDwell {
+                extension_ms,
+                max_dwell_ms,
+            } => Box::new(ExtendedDwell {
+                dwell,
+                extension: Duration::milliseconds(*extension_ms as i64),
+                max_dwell: max_dwell_ms
+                    .map(|max_dwell_ms| Duration::milliseconds(max_dwell_ms as i64)),
+            }),
         }
     }
 }
@@ -27,3 +116,7 @@ fn default_channels() -> Vec<u64> {
 fn default_channel_mod_freq_ms() -> u64 {
     300
 }
+
+fn default_extension_ms() -> u64 {
+    30_000
+}
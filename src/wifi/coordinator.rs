@@ -0,0 +1,364 @@
+use std::{sync::Arc, time::Duration};
+
+use log::debug;
+use tokio::{sync::Mutex, time::sleep};
+
+use super::{apply_retunes, ChannelPlan, Nl80211Controller, WifiConfig, WifiInterface};
+
+/// Splits `channels` between `adapters`, given as the plan each of them can tune to, skipping
+/// the `pinned` channels other adapters are tracking drones on. A channel only goes to an
+/// adapter that supports it. Shares are disjoint unless an adapter supports fewer free
+/// channels than it has to share, in which case it doubles up.
+pub fn allocate_channels(
+    channels: &[u64],
+    pinned: &[u64],
+    adapters: &[&ChannelPlan],
+) -> Vec<Vec<u64>> {
+    let mut free: Vec<u64> = channels
+        .iter()
+        .copied()
+        .filter(|channel| !pinned.contains(channel))
+        .collect();
+
+    // every channel is pinned, keep scanning the whole plan
+    if free.is_empty() {
+        free = channels.to_vec();
+    }
+
+    let supported_by = |channel: u64| {
+        (0..adapters.len())
+            .filter(move |idx| adapters[*idx].contains(channel))
+            .collect::<Vec<usize>>()
+    };
+
+    // channels few adapters can tune to are handed out first, so a dual-band adapter isn't
+    // already loaded with the 2.4 GHz channels when the 5 GHz ones come up
+    let mut by_support = free.clone();
+    by_support.sort_by_key(|channel| supported_by(*channel).len());
+
+    let mut shares = vec![vec![]; adapters.len()];
+
+    for channel in by_support {
+        if let Some(idx) = supported_by(channel)
+            .into_iter()
+            .min_by_key(|idx| shares[*idx].len())
+        {
+            shares[idx].push(channel);
+        }
+    }
+
+    for (idx, (share, plan)) in shares.iter_mut().zip(adapters).enumerate() {
+        share.sort_by_key(|channel| free.iter().position(|free| free == channel));
+
+        if share.is_empty() {
+            let supported: Vec<u64> = free
+                .iter()
+                .copied()
+                .filter(|channel| plan.contains(*channel))
+                .collect();
+
+            if supported.is_empty() {
+                // every channel it can tune to is pinned, it keeps scanning them
+                *share = channels
+                    .iter()
+                    .copied()
+                    .filter(|channel| plan.contains(*channel))
+                    .collect();
+            } else {
+                share.push(supported[idx % supported.len()]);
+            }
+        }
+    }
+
+    shares
+}
+
+/// Hops several adapters over one channel plan. An adapter that is tracking a drone stays on
+/// its channel, and the rest of the plan is shared between the adapters still scanning.
+pub struct ChannelCoordinator {
+    channels: Vec<u64>,
+    channel_mod_freq_ms: u64,
+    interfaces: Vec<Arc<Mutex<WifiInterface>>>,
+}
+
+impl ChannelCoordinator {
+    pub async fn init(config: &WifiConfig) -> anyhow::Result<Self> {
+        let plan = config.channel_plan()?;
+        let controller = Nl80211Controller::open_shared()?;
+        let mut interfaces = vec![];
+
+        for device_name in config.device_names() {
+            interfaces.push(
+                WifiInterface::init_device(
+                    &device_name,
+                    &plan,
+                    config.channel_mod_freq_ms,
+                    &config.hopping,
+                    controller.clone(),
+                )
+                .await?,
+            );
+        }
+
+        // planned channels at least one adapter supports
+        let channels: Vec<u64> = plan
+            .numbers()
+            .into_iter()
+            .filter(|channel| {
+                interfaces
+                    .iter()
+                    .any(|interface| interface.plan.contains(*channel))
+            })
+            .collect();
+
+        let plans: Vec<&ChannelPlan> = interfaces.iter().map(|interface| &interface.plan).collect();
+        let shares = allocate_channels(&channels, &[], &plans);
+
+        for (interface, share) in interfaces.iter_mut().zip(shares) {
+            if interface.set_channels(share) {
+                interface.apply_channel()?;
+            }
+        }
+
+        Ok(ChannelCoordinator::new(
+            channels,
+            config.channel_mod_freq_ms,
+            interfaces,
+        ))
+    }
+
+    /// Coordinates adapters that are already set up to hop over `channels`.
+    pub fn new(
+        channels: Vec<u64>,
+        channel_mod_freq_ms: u64,
+        interfaces: Vec<WifiInterface>,
+    ) -> Self {
+        ChannelCoordinator {
+            channels,
+            channel_mod_freq_ms,
+            interfaces: interfaces
+                .into_iter()
+                .map(|interface| Arc::new(Mutex::new(interface)))
+                .collect(),
+        }
+    }
+
+    pub fn interfaces(&self) -> Vec<Arc<Mutex<WifiInterface>>> {
+        self.interfaces.clone()
+    }
+
+    pub async fn run_loop(&self) -> anyhow::Result<()> {
+        loop {
+            self.rebalance().await;
+
+            sleep(Duration::from_millis(self.channel_mod_freq_ms)).await;
+        }
+    }
+
+    async fn rebalance(&self) {
+        let mut retunes = vec![];
+
+        {
+            let mut interfaces = vec![];
+
+            for interface in &self.interfaces {
+                interfaces.push(interface.lock().await);
+            }
+
+            let tracking: Vec<bool> = interfaces
+                .iter()
+                .map(|interface| interface.is_tracking())
+                .collect();
+
+            let pinned: Vec<u64> = interfaces
+                .iter()
+                .zip(&tracking)
+                .filter(|(_, tracking)| **tracking)
+                .map(|(interface, _)| interface.channel)
+                .collect();
+
+            let scanning: Vec<&ChannelPlan> = interfaces
+                .iter()
+                .zip(&tracking)
+                .filter(|(_, tracking)| !**tracking)
+                .map(|(interface, _)| &interface.plan)
+                .collect();
+            let mut shares = allocate_channels(&self.channels, &pinned, &scanning).into_iter();
+
+            for (interface, tracking) in interfaces.iter_mut().zip(&tracking) {
+                if *tracking {
+                    debug!("{} pinned to channel {}", interface.name, interface.channel);
+                    continue;
+                }
+
+                let share = shares.next().unwrap_or_default();
+
+                if interface.set_channels(share) {
+                    retunes.push(interface.retune());
+                } else if interface.should_change_channel() {
+                    interface.hop_channel();
+                    retunes.push(interface.retune());
+                }
+            }
+        }
+
+        // the adapters are unlocked while netlink tunes them
+        apply_retunes(retunes).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{allocate_channels, ChannelCoordinator};
+    use crate::wifi::nl80211::mock::MockTransport;
+    use crate::wifi::{ChannelPlan, HoppingConfig, Nl80211Controller, WifiInterface};
+
+    fn interface(name: &str, channel: u64, transport: MockTransport) -> WifiInterface {
+        let mut interface = WifiInterface::new(
+            name.to_string(),
+            ChannelPlan::custom(&[1, 6, 11]).unwrap(),
+            1000,
+            &HoppingConfig::RoundRobin,
+            Nl80211Controller::shared(transport),
+        );
+
+        interface.channel = channel;
+        interface
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_pins_tracking_adapter() {
+        // round-robin never holds a channel itself, pinning still keeps the drone in view
+        let mut tracking = interface("wlan0mon", 6, MockTransport::default());
+        tracking.update_last_odid_received(Utc::now());
+
+        let scanning = interface(
+            "wlan1mon",
+            6,
+            MockTransport::default().tune("wlan1mon", 2412),
+        );
+
+        let coordinator = ChannelCoordinator::new(vec![1, 6, 11], 1000, vec![tracking, scanning]);
+
+        coordinator.rebalance().await;
+
+        let interfaces = coordinator.interfaces();
+        let tracking = interfaces[0].lock().await;
+        let scanning = interfaces[1].lock().await;
+
+        assert!(tracking.is_tracking());
+        assert_eq!(tracking.channel, 6);
+        assert_eq!(tracking.channels, vec![1, 6, 11]);
+        assert_eq!(scanning.channel, 1);
+        assert_eq!(scanning.channels, vec![1, 11]);
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_mixed_bands() {
+        // the 5 GHz channels only go to the adapter that can tune to them
+        let narrow = interface("wlan0mon", 1, MockTransport::default());
+        let mut wide = WifiInterface::new(
+            "wlan1mon".to_string(),
+            ChannelPlan::custom(&[1, 6, 11, 36, 149]).unwrap(),
+            1000,
+            &HoppingConfig::RoundRobin,
+            Nl80211Controller::shared(MockTransport::default().tune("wlan1mon", 5180)),
+        );
+        wide.channel = 1;
+
+        let coordinator =
+            ChannelCoordinator::new(vec![1, 6, 11, 36, 149], 1000, vec![narrow, wide]);
+
+        coordinator.rebalance().await;
+
+        let interfaces = coordinator.interfaces();
+        let narrow = interfaces[0].lock().await;
+        let wide = interfaces[1].lock().await;
+
+        assert_eq!(narrow.channels, vec![1, 6, 11]);
+        assert_eq!(narrow.channel, 1);
+        assert_eq!(wide.channels, vec![36, 149]);
+        assert_eq!(wide.channel, 36);
+    }
+
+    fn plan(channels: &[u64]) -> ChannelPlan {
+        ChannelPlan::custom(channels).unwrap()
+    }
+
+    #[test]
+    fn test_allocate_channels() {
+        let plan = plan(&[1, 6, 11, 149]);
+
+        assert_eq!(
+            allocate_channels(&[1, 6, 11], &[], &[&plan]),
+            vec![vec![1, 6, 11]]
+        );
+        assert_eq!(
+            allocate_channels(&[1, 6, 11], &[], &[&plan, &plan]),
+            vec![vec![1, 11], vec![6]]
+        );
+        assert_eq!(
+            allocate_channels(&[1, 6, 11, 149], &[], &[&plan, &plan, &plan]),
+            vec![vec![1, 149], vec![6], vec![11]]
+        );
+    }
+
+    #[test]
+    fn test_allocate_channels_with_pinned() {
+        let plan = plan(&[1, 6, 11, 149]);
+
+        // the adapter on 6 is tracking, the other two split the rest
+        assert_eq!(
+            allocate_channels(&[1, 6, 11, 149], &[6], &[&plan, &plan]),
+            vec![vec![1, 149], vec![11]]
+        );
+        assert_eq!(
+            allocate_channels(&[1, 6], &[1, 6], &[&plan]),
+            vec![vec![1, 6]]
+        );
+    }
+
+    #[test]
+    fn test_allocate_channels_mixed_bands() {
+        let narrow = plan(&[1, 6, 11]);
+        let wide = plan(&[1, 6, 11, 36, 44, 149, 161]);
+        let channels = [1, 6, 11, 36, 44, 149, 161];
+
+        assert_eq!(
+            allocate_channels(&channels, &[], &[&narrow, &wide]),
+            vec![vec![1, 6, 11], vec![36, 44, 149, 161]]
+        );
+        // the dual-band adapter helps out on 2.4 GHz once it has its share of 5 GHz
+        assert_eq!(
+            allocate_channels(&[1, 6, 11, 36], &[], &[&narrow, &wide]),
+            vec![vec![1, 6], vec![11, 36]]
+        );
+        // everything the 2.4 GHz adapter supports is pinned, it isn't handed 5 GHz channels
+        assert_eq!(
+            allocate_channels(&[1, 36, 149], &[1], &[&narrow, &wide]),
+            vec![vec![1], vec![36, 149]]
+        );
+        // its only free channel is shared rather than left to a stale list
+        assert_eq!(
+            allocate_channels(&[1, 6, 36], &[1], &[&narrow, &wide]),
+            vec![vec![6], vec![36]]
+        );
+    }
+
+    #[test]
+    fn test_allocate_channels_more_adapters_than_channels() {
+        let plan = plan(&[1, 6]);
+
+        assert_eq!(
+            allocate_channels(&[1, 6], &[], &[&plan, &plan, &plan]),
+            vec![vec![1], vec![6], vec![1]]
+        );
+        assert_eq!(
+            allocate_channels(&[], &[], &[&plan, &plan]),
+            vec![vec![], vec![]]
+        );
+        assert!(allocate_channels(&[1, 6], &[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,430 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt::Debug,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// ODID traffic and listening time of one channel.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChannelStats {
+    pub hits: u64,
+    /// Hits within the scheduler's window, what hit-weighted hopping goes by.
+    pub recent_hits: usize,
+    pub visits: u64,
+    pub listen_ms: i64,
+    pub last_hit: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    hit_times: VecDeque<DateTime<Utc>>,
+}
+
+impl ChannelStats {
+    fn trim(&mut self, since: DateTime<Utc>) {
+        while self.hit_times.front().is_some_and(|hit| *hit < since) {
+            self.hit_times.pop_front();
+        }
+
+        self.recent_hits = self.hit_times.len();
+    }
+}
+
+/// Decides when an adapter leaves its channel and where it goes next.
+pub trait HoppingStrategy: Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Whether recent ODID traffic holds the channel for longer than the scheduler's own hold,
+    /// which every strategy shares.
+    fn is_holding(&self, _stats: &ChannelStats, _now: DateTime<Utc>) -> bool {
+        false
+    }
+
+    /// Whether to leave a channel after listening to it for `elapsed`.
+    fn should_hop(&self, stats: &ChannelStats, elapsed: Duration, now: DateTime<Utc>) -> bool;
+
+    /// The channel to listen to after `current`.
+    fn next_channel(
+        &mut self,
+        current: u64,
+        channels: &[u64],
+        stats: &BTreeMap<u64, ChannelStats>,
+    ) -> u64;
+
+    fn clone_box(&self) -> Box<dyn HoppingStrategy>;
+}
+
+impl Clone for Box<dyn HoppingStrategy> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+fn round_robin(current: u64, channels: &[u64]) -> u64 {
+    match channels.iter().position(|channel| *channel == current) {
+        Some(idx) => channels[(idx + 1) % channels.len()],
+        None => channels.first().copied().unwrap_or(current),
+    }
+}
+
+/// Every channel in turn, for the same time.
+#[derive(Debug, Clone)]
+pub struct RoundRobin {
+    pub dwell: Duration,
+}
+
+impl HoppingStrategy for RoundRobin {
+    fn name(&self) -> &'static str {
+        "round_robin"
+    }
+
+    fn should_hop(&self, _stats: &ChannelStats, elapsed: Duration, _now: DateTime<Utc>) -> bool {
+        elapsed >= self.dwell
+    }
+
+    fn next_channel(
+        &mut self,
+        current: u64,
+        channels: &[u64],
+        _stats: &BTreeMap<u64, ChannelStats>,
+    ) -> u64 {
+        round_robin(current, channels)
+    }
+
+    fn clone_box(&self) -> Box<dyn HoppingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Channels in turn, staying on a channel while ODID keeps arriving on it: until `extension`
+/// after the last hit, and at most `max_dwell` when set.
+#[derive(Debug, Clone)]
+pub struct ExtendedDwell {
+    pub dwell: Duration,
+    pub extension: Duration,
+    pub max_dwell: Option<Duration>,
+}
+
+impl HoppingStrategy for ExtendedDwell {
+    fn name(&self) -> &'static str {
+        "extended_dwell"
+    }
+
+    fn is_holding(&self, stats: &ChannelStats, now: DateTime<Utc>) -> bool {
+        stats
+            .last_hit
+            .is_some_and(|last_hit| now.signed_duration_since(last_hit) <= self.extension)
+    }
+
+    fn should_hop(&self, stats: &ChannelStats, elapsed: Duration, now: DateTime<Utc>) -> bool {
+        if elapsed < self.dwell {
+            return false;
+        }
+
+        if self.max_dwell.is_some_and(|max_dwell| elapsed >= max_dwell) {
+            return true;
+        }
+
+        !self.is_holding(stats, now)
+    }
+
+    fn next_channel(
+        &mut self,
+        current: u64,
+        channels: &[u64],
+        _stats: &BTreeMap<u64, ChannelStats>,
+    ) -> u64 {
+        round_robin(current, channels)
+    }
+
+    fn clone_box(&self) -> Box<dyn HoppingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Visits channels in proportion to their recent hits, while still visiting quiet channels:
+/// each channel weighs one plus its recent hits. Uses smooth weighted round-robin, so the
+/// order is deterministic and a heavy channel doesn't starve the others.
+#[derive(Debug, Clone)]
+pub struct HitWeighted {
+    pub dwell: Duration,
+    current_weights: HashMap<u64, i64>,
+}
+
+impl HitWeighted {
+    pub fn new(dwell: Duration) -> Self {
+        HitWeighted {
+            dwell,
+            current_weights: HashMap::new(),
+        }
+    }
+}
+
+impl HoppingStrategy for HitWeighted {
+    fn name(&self) -> &'static str {
+        "hit_weighted"
+    }
+
+    fn should_hop(&self, _stats: &ChannelStats, elapsed: Duration, _now: DateTime<Utc>) -> bool {
+        elapsed >= self.dwell
+    }
+
+    fn next_channel(
+        &mut self,
+        current: u64,
+        channels: &[u64],
+        stats: &BTreeMap<u64, ChannelStats>,
+    ) -> u64 {
+        let weight = |channel: &u64| {
+            1 + stats
+                .get(channel)
+                .map(|stats| stats.recent_hits as i64)
+                .unwrap_or(0)
+        };
+
+        let total: i64 = channels.iter().map(weight).sum();
+        let mut next: Option<(u64, i64)> = None;
+
+        for channel in channels {
+            let current_weight = self.current_weights.entry(*channel).or_insert(0);
+            *current_weight += weight(channel);
+
+            if next.is_none_or(|(_, best)| *current_weight > best) {
+                next = Some((*channel, *current_weight));
+            }
+        }
+
+        match next {
+            Some((channel, _)) => {
+                *self.current_weights.get_mut(&channel).unwrap() -= total;
+                channel
+            }
+            None => current,
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn HoppingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Per-channel statistics of one adapter, and the strategy hopping it.
+#[derive(Debug, Clone)]
+pub struct ChannelScheduler {
+    strategy: Box<dyn HoppingStrategy>,
+    stats: BTreeMap<u64, ChannelStats>,
+    entered_at: DateTime<Utc>,
+}
+
+impl ChannelScheduler {
+    // how far back hits count as recent
+    const HIT_WINDOW_MINUTES: i64 = 5;
+    // how long a hit holds the channel whatever the strategy, e.g. pinning the adapter to it
+    const HOLD_SECONDS: i64 = 30;
+
+    pub fn new(strategy: Box<dyn HoppingStrategy>, now: DateTime<Utc>) -> Self {
+        ChannelScheduler {
+            strategy,
+            stats: BTreeMap::new(),
+            entered_at: now,
+        }
+    }
+
+    pub fn strategy_name(&self) -> &'static str {
+        self.strategy.name()
+    }
+
+    pub fn stats(&self) -> &BTreeMap<u64, ChannelStats> {
+        &self.stats
+    }
+
+    pub fn record_hit(&mut self, channel: u64, at: DateTime<Utc>) {
+        let stats = self.stats.entry(channel).or_default();
+
+        stats.hits += 1;
+        stats.last_hit = Some(at);
+        stats.hit_times.push_back(at);
+        stats.trim(at - Duration::minutes(ChannelScheduler::HIT_WINDOW_MINUTES));
+    }
+
+    /// Whether a drone was heard on the channel recently enough to stay on it. Strategies can
+    /// hold longer, not shorter.
+    pub fn is_holding(&self, channel: u64, now: DateTime<Utc>) -> bool {
+        let stats = self.channel_stats(channel);
+        let hold = Duration::seconds(ChannelScheduler::HOLD_SECONDS);
+
+        stats
+            .last_hit
+            .is_some_and(|last_hit| now.signed_duration_since(last_hit) <= hold)
+            || self.strategy.is_holding(&stats, now)
+    }
+
+    pub fn should_hop(&self, channel: u64, now: DateTime<Utc>) -> bool {
+        let elapsed = now.signed_duration_since(self.entered_at);
+
+        self.strategy
+            .should_hop(&self.channel_stats(channel), elapsed, now)
+    }
+
+    /// Leaves `current` for the strategy's next channel.
+    pub fn hop(&mut self, current: u64, channels: &[u64], now: DateTime<Utc>) -> u64 {
+        let since = now - Duration::minutes(ChannelScheduler::HIT_WINDOW_MINUTES);
+
+        for stats in self.stats.values_mut() {
+            stats.trim(since);
+        }
+
+        let next = self.strategy.next_channel(current, channels, &self.stats);
+
+        self.enter(current, next, now);
+
+        next
+    }
+
+    /// Records moving from `previous` to `channel`, whichever decided it.
+    pub fn enter(&mut self, previous: u64, channel: u64, now: DateTime<Utc>) {
+        let listened = now.signed_duration_since(self.entered_at);
+
+        self.stats.entry(previous).or_default().listen_ms += listened.num_milliseconds();
+        self.stats.entry(channel).or_default().visits += 1;
+        self.entered_at = now;
+    }
+
+    fn channel_stats(&self, channel: u64) -> ChannelStats {
+        self.stats.get(&channel).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::{ChannelScheduler, ExtendedDwell, HitWeighted, RoundRobin};
+
+    fn start() -> DateTime<Utc> {
+        DateTime::from_timestamp(1717243200, 0).unwrap()
+    }
+
+    fn hops(scheduler: &mut ChannelScheduler, channels: &[u64], count: usize) -> Vec<u64> {
+        let mut channel = channels[0];
+        let mut now = start();
+        let mut visited = vec![];
+
+        for _ in 0..count {
+            now += Duration::seconds(1);
+            channel = scheduler.hop(channel, channels, now);
+            visited.push(channel);
+        }
+
+        visited
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let strategy = RoundRobin {
+            dwell: Duration::milliseconds(300),
+        };
+        let mut scheduler = ChannelScheduler::new(Box::new(strategy), start());
+
+        assert!(!scheduler.should_hop(1, start() + Duration::milliseconds(299)));
+        assert!(scheduler.should_hop(1, start() + Duration::milliseconds(300)));
+
+        // a hit holds the channel for pinning, but doesn't keep round-robin on it
+        scheduler.record_hit(1, start());
+        assert!(scheduler.is_holding(1, start() + Duration::seconds(30)));
+        assert!(!scheduler.is_holding(1, start() + Duration::seconds(31)));
+        assert!(scheduler.should_hop(1, start() + Duration::seconds(1)));
+
+        assert_eq!(hops(&mut scheduler, &[1, 6, 11], 4), vec![6, 11, 1, 6]);
+        // a channel dropped from the list restarts the rotation
+        assert_eq!(scheduler.hop(36, &[1, 6, 11], start()), 1);
+    }
+
+    #[test]
+    fn test_extended_dwell() {
+        let strategy = ExtendedDwell {
+            dwell: Duration::seconds(1),
+            extension: Duration::seconds(30),
+            max_dwell: Some(Duration::minutes(2)),
+        };
+        let mut scheduler = ChannelScheduler::new(Box::new(strategy), start());
+
+        assert!(scheduler.should_hop(6, start() + Duration::seconds(1)));
+
+        scheduler.record_hit(6, start() + Duration::seconds(10));
+
+        assert!(scheduler.is_holding(6, start() + Duration::seconds(20)));
+        assert!(!scheduler.should_hop(6, start() + Duration::seconds(20)));
+        // the dwell runs out once traffic stops
+        assert!(scheduler.should_hop(6, start() + Duration::seconds(41)));
+
+        // and is capped while traffic continues
+        scheduler.record_hit(6, start() + Duration::seconds(119));
+        assert!(!scheduler.should_hop(6, start() + Duration::seconds(119)));
+        assert!(scheduler.should_hop(6, start() + Duration::seconds(120)));
+
+        // hits on another channel don't hold this one
+        assert!(!scheduler.is_holding(1, start() + Duration::seconds(119)));
+
+        // a longer extension holds past the shared 30 seconds
+        let strategy = ExtendedDwell {
+            dwell: Duration::seconds(1),
+            extension: Duration::seconds(60),
+            max_dwell: None,
+        };
+        let mut scheduler = ChannelScheduler::new(Box::new(strategy), start());
+
+        scheduler.record_hit(6, start());
+        assert!(scheduler.is_holding(6, start() + Duration::seconds(45)));
+        assert!(!scheduler.is_holding(6, start() + Duration::seconds(61)));
+    }
+
+    #[test]
+    fn test_hit_weighted() {
+        let mut scheduler =
+            ChannelScheduler::new(Box::new(HitWeighted::new(Duration::seconds(1))), start());
+
+        // without hits it is plain round-robin
+        assert_eq!(hops(&mut scheduler, &[1, 6, 11], 3), vec![1, 6, 11]);
+
+        let mut scheduler =
+            ChannelScheduler::new(Box::new(HitWeighted::new(Duration::seconds(1))), start());
+
+        for _ in 0..4 {
+            scheduler.record_hit(6, start());
+        }
+
+        // channel 6 weighs 5 against 1 and 1, quiet channels are still visited
+        let visited = hops(&mut scheduler, &[1, 6, 11], 7);
+
+        assert_eq!(visited.iter().filter(|channel| **channel == 6).count(), 5);
+        assert!(visited.contains(&1));
+        assert!(visited.contains(&11));
+    }
+
+    #[test]
+    fn test_channel_stats() {
+        let strategy = RoundRobin {
+            dwell: Duration::seconds(1),
+        };
+        let mut scheduler = ChannelScheduler::new(Box::new(strategy), start());
+
+        scheduler.record_hit(1, start());
+        scheduler.record_hit(1, start() + Duration::seconds(1));
+        scheduler.hop(1, &[1, 6], start() + Duration::seconds(2));
+
+        let stats = &scheduler.stats()[&1];
+
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.recent_hits, 2);
+        assert_eq!(stats.listen_ms, 2000);
+        assert_eq!(stats.last_hit, Some(start() + Duration::seconds(1)));
+        assert_eq!(scheduler.stats()[&6].visits, 1);
+
+        // old hits age out of the window, the total stays
+        scheduler.hop(6, &[1, 6], start() + Duration::minutes(10));
+
+        assert_eq!(scheduler.stats()[&1].hits, 2);
+        assert_eq!(scheduler.stats()[&1].recent_hits, 0);
+        assert_eq!(scheduler.stats()[&6].listen_ms, 598_000);
+    }
+}
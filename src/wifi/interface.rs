@@ -1,189 +1,360 @@
-use std::{process::Command, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use chrono::{DateTime, Utc};
-use log::{debug, trace};
+use log::{debug, error, trace, warn};
 use tokio::{sync::Mutex, time::sleep};
 
-use super::WifiConfig;
+use super::{
+    Channel, ChannelPlan, ChannelScheduler, HoppingConfig, Nl80211Controller, Nl80211Error,
+    Nl80211Transport, SharedController, WifiConfig,
+};
 
 #[derive(Debug, Clone)]
 pub struct WifiInterface {
     pub name: String,
     pub channel: u64,
     pub channels: Vec<u64>,
+    /// The channels of the plan the adapter supports, `channels` is a subset of these.
+    pub plan: ChannelPlan,
     pub last_odid_received: Option<DateTime<Utc>>,
     pub channel_mod_freq_ms: u64,
+    pub scheduler: ChannelScheduler,
+    controller: SharedController,
 }
 
-impl Default for WifiInterface {
-    fn default() -> Self {
+//fn (interface *WifiInterface) init(config WifiConfig)
+
+impl WifiInterface {
+    /// An adapter already in monitor mode as `name`, hopping over every channel of `plan`.
+    pub fn new(
+        name: String,
+        plan: ChannelPlan,
+        channel_mod_freq_ms: u64,
+        hopping: &HoppingConfig,
+        controller: SharedController,
+    ) -> Self {
+        let channels = plan.numbers();
+
         WifiInterface {
-            name: "wlan0".to_string(),
-            channels: vec![1, 6, 11],
-            channel: 6,
+            name,
+            channel: channels.first().copied().unwrap_or_default(),
+            channels,
+            plan,
             last_odid_received: None,
-            channel_mod_freq_ms: 1000,
+            channel_mod_freq_ms,
+            scheduler: ChannelScheduler::new(hopping.strategy(channel_mod_freq_ms), Utc::now()),
+            controller,
         }
     }
-}
-
-//fn (interface *WifiInterface) init(config WifiConfig)
-
-impl WifiInterface {
-    // Time in seconds to change the channel
-    const TIME_TO_CHANGE_CHANNEL: i64 = 30;
 
     pub async fn init(config: WifiConfig) -> anyhow::Result<Self> {
-        let wifi_interface = WifiInterface {
-            name: config.device_name,
-            channels: config.channels.clone(),
-            channel: config.channels[0],
-            last_odid_received: None,
-            channel_mod_freq_ms: config.channel_mod_freq_ms,
+        WifiInterface::init_device(
+            &config.device_name,
+            &config.channel_plan()?,
+            config.channel_mod_freq_ms,
+            &config.hopping,
+            Nl80211Controller::open_shared()?,
+        )
+        .await
+    }
+
+    /// Puts the device into monitor mode and checks `plan` against the frequencies it
+    /// supports, hopping over the supported channels.
+    pub async fn init_device(
+        device_name: &str,
+        plan: &ChannelPlan,
+        channel_mod_freq_ms: u64,
+        hopping: &HoppingConfig,
+        controller: SharedController,
+    ) -> anyhow::Result<Self> {
+        let (name, frequencies) = {
+            let mut nl80211 = lock_controller(&controller);
+
+            // Enable monitoring mode, capture happens on the monitor interface
+            let name = enable_monitor_mode(&mut nl80211, device_name)?;
+            let frequencies = nl80211.supported_frequencies(&name)?;
+
+            (name, frequencies)
         };
 
-        // Enable monitoring mode
-        enable_monitor_mode(wifi_interface.name.as_str()).unwrap();
+        let unsupported = plan.unsupported(&frequencies);
+        let plan = plan.supported(&frequencies);
+
+        if plan.channels.is_empty() {
+            anyhow::bail!("{} supports none of the planned channels", device_name);
+        }
+
+        if !unsupported.is_empty() {
+            warn!(
+                "{} doesn't support channels {:?}, skipping them",
+                device_name, unsupported
+            );
+        }
+
+        let wifi_interface =
+            WifiInterface::new(name, plan, channel_mod_freq_ms, hopping, controller);
+
+        wifi_interface.apply_channel()?;
 
         Ok(wifi_interface)
     }
 
     pub async fn run_loop(interface: Arc<Mutex<WifiInterface>>) -> anyhow::Result<()> {
         loop {
-            let mut wifi_interface = interface.lock().await;
-            if wifi_interface.should_change_channel() {
-                wifi_interface.adjust_channel();
-            }
+            let (retune, channel_mod_freq_ms) = {
+                let mut wifi_interface = interface.lock().await;
+                let retune = if wifi_interface.should_change_channel() {
+                    wifi_interface.hop_channel();
+                    Some(wifi_interface.retune())
+                } else {
+                    None
+                };
+
+                (retune, wifi_interface.channel_mod_freq_ms)
+            };
 
-            sleep(Duration::from_millis(wifi_interface.channel_mod_freq_ms)).await;
+            apply_retunes(retune.into_iter().collect()).await;
+
+            sleep(Duration::from_millis(channel_mod_freq_ms)).await;
         }
     }
 
     pub fn update_last_odid_received(&mut self, timestamp: DateTime<Utc>) {
         self.last_odid_received = Some(timestamp);
+        self.scheduler.record_hit(self.channel, timestamp);
     }
 
-    pub fn should_change_channel(&self) -> bool {
-        let current_time = Utc::now();
+    /// Whether ODID was received on the current channel recently enough to stay on it.
+    pub fn is_tracking(&self) -> bool {
+        self.scheduler.is_holding(self.channel, Utc::now())
+    }
 
-        match self.last_odid_received {
-            Some(last_odid_received) => {
-                let time_diff = current_time
-                    .signed_duration_since(last_odid_received)
-                    .num_seconds();
+    /// Replaces the channels to hop over, returning whether the current channel had to change.
+    /// Channels the adapter doesn't support are left out.
+    pub fn set_channels(&mut self, mut channels: Vec<u64>) -> bool {
+        channels.retain(|channel| self.plan.contains(*channel));
 
-                debug!(
-                    "Tracking on channel {} for {} seconds",
-                    self.channel, time_diff
-                );
+        if channels.is_empty() || channels == self.channels {
+            return false;
+        }
 
-                time_diff > WifiInterface::TIME_TO_CHANGE_CHANNEL
-            }
-            None => {
-                let time_diff = current_time.signed_duration_since(
-                    Utc::now() - chrono::Duration::seconds(WifiInterface::TIME_TO_CHANGE_CHANNEL),
-                );
+        self.channels = channels;
 
-                time_diff.num_seconds() > 5
-            }
+        if self.channels.contains(&self.channel) {
+            return false;
         }
-    }
 
-    pub fn adjust_channel(&mut self) {
-        let num_channels = self.channels.len();
-        let max_idx = num_channels - 1;
+        let previous = self.channel;
 
-        let current_idx = self
-            .channels
-            .iter()
-            .position(|channel| *channel == self.channel)
-            .unwrap();
+        self.channel = self.channels[0];
+        self.last_odid_received = None;
+        self.scheduler.enter(previous, self.channel, Utc::now());
 
-        println!("current idx {} max idx {}", current_idx, max_idx);
+        true
+    }
 
-        self.channel = if current_idx == max_idx {
-            *self.channels.get(0).unwrap()
-        } else {
-            //*self.channel.iter().next().unwrap()
-            *self.channels.get(current_idx + 1).unwrap()
-        };
+    pub fn should_change_channel(&self) -> bool {
+        if let Some(last_odid_received) = self.last_odid_received {
+            debug!(
+                "Tracking on channel {} for {} seconds",
+                self.channel,
+                Utc::now()
+                    .signed_duration_since(last_odid_received)
+                    .num_seconds()
+            );
+        }
+
+        self.scheduler.should_hop(self.channel, Utc::now())
+    }
 
-        //match self.channel {
-        //    // 2.4 GHz channels
-        //    1 => self.channel = 6,
-        //    6 => self.channel = 9,
-        //    9 => self.channel = 11,
-        //    11 => self.channel = 1,
-        //    // 5 GHz channels
-        //    _ => self.channel = 6,
-        //}
+    /// Moves to the strategy's next channel without tuning the adapter, see `retune`.
+    pub fn hop_channel(&mut self) {
+        self.channel = self.scheduler.hop(self.channel, &self.channels, Utc::now());
 
         debug!("Adjusting channel to {}", self.channel);
 
         self.last_odid_received = None;
+    }
+
+    pub fn adjust_channel(&mut self) -> Result<(), Nl80211Error> {
+        self.hop_channel();
+        self.apply_channel()
+    }
 
-        // Change adjust_channel
-        let _ = Command::new("iwconfig")
-            .args([
-                self.name.as_str(),
-                "channel",
-                self.channel.to_string().as_str(),
-            ])
-            .output()
-            .expect("failed to execute process");
+    /// Tunes the adapter to `self.channel`.
+    pub fn apply_channel(&self) -> Result<(), Nl80211Error> {
+        self.retune().apply()
+    }
+
+    /// Tuning the adapter to `self.channel`, to apply once the adapter's lock is released.
+    pub fn retune(&self) -> Retune {
+        Retune {
+            name: self.name.clone(),
+            channel: self.channel,
+            planned: self
+                .plan
+                .channel(self.channel)
+                .map(|planned| planned.channel),
+            controller: self.controller.clone(),
+        }
     }
 }
 
-pub fn enable_monitor_mode(device: &str) -> Result<(), String> {
-    // Check if the device is already in monitoring mode
-    let check_mode = Command::new("iwconfig")
-        .arg(device)
-        .output()
-        .expect("failed to execute process");
+/// A channel change decided under an adapter's lock. Netlink calls block, so it is applied
+/// after the lock is released rather than holding up the capture and the web server.
+pub struct Retune {
+    pub name: String,
+    pub channel: u64,
+    planned: Option<Channel>,
+    controller: SharedController,
+}
 
-    let output = String::from_utf8_lossy(&check_mode.stdout);
+impl Retune {
+    pub fn apply(&self) -> Result<(), Nl80211Error> {
+        let planned = self
+            .planned
+            .ok_or(Nl80211Error::UnknownChannel(self.channel))?;
 
-    if !output.contains("Mode:Monitor") {
-        // Enable monitoring mode using airmon-ng
-        let start_mon = Command::new("sudo")
-            .args(["airmon-ng", "start", device])
-            .output()
-            .expect("failed to execute process");
+        let interface = lock_controller(&self.controller).set_channel(&self.name, planned)?;
 
-        println!("{:?} {:?} {:?}", start_mon.status, output, check_mode);
+        trace!("{} is on {:?}", interface.name, interface.channel);
+
+        Ok(())
+    }
+}
+
+/// Applies `retunes` on a blocking thread, logging the ones that fail.
+pub async fn apply_retunes(retunes: Vec<Retune>) {
+    if retunes.is_empty() {
+        return;
+    }
+
+    let applied = tokio::task::spawn_blocking(move || {
+        for retune in retunes {
+            if let Err(e) = retune.apply() {
+                error!(
+                    "Couldn't set {} to channel {}: {}",
+                    retune.name, retune.channel, e
+                );
+            }
+        }
+    })
+    .await;
+
+    if let Err(e) = applied {
+        error!("Channel change task failed: {}", e);
     }
+}
+
+// a panic mid-request leaves nothing half-done in the controller itself
+fn lock_controller(
+    controller: &SharedController,
+) -> std::sync::MutexGuard<'_, Nl80211Controller<Box<dyn Nl80211Transport>>> {
+    controller
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Puts `device` into monitor mode, returning the name of the monitor interface to capture
+/// on, which may differ from `device` (e.g. `wlan0mon`).
+pub fn enable_monitor_mode<T: Nl80211Transport>(
+    controller: &mut Nl80211Controller<T>,
+    device: &str,
+) -> Result<String, Nl80211Error> {
+    let interface = controller.enable_monitor(device)?;
 
-    // Change channel to 6
-    let _ = Command::new("iwconfig")
-        .args([device, "channel", "6"])
-        .output()
-        .expect("failed to execute process");
+    debug!("Monitoring {} on {}", device, interface.name);
 
-    Ok(())
+    Ok(interface.name)
 }
 
 #[cfg(test)]
 pub mod test {
+    use chrono::{Duration, Utc};
+
     use super::WifiInterface;
+    use crate::wifi::nl80211::mock::MockTransport;
+    use crate::wifi::{ChannelPlan, HoppingConfig, Nl80211Controller};
+
+    fn interface(transport: MockTransport) -> WifiInterface {
+        WifiInterface::new(
+            "wlan0mon".to_string(),
+            ChannelPlan::custom(&[1, 6, 11]).unwrap(),
+            1000,
+            &HoppingConfig::default(),
+            Nl80211Controller::shared(transport),
+        )
+    }
 
     #[test]
     fn test_device_switcheroo() {
+        // every hop is checked against the frequency the adapter reports back
+        let transport = MockTransport::default()
+            .tune("wlan0mon", 2462)
+            .tune("wlan0mon", 2412);
+
         let mut interface = WifiInterface {
-            name: "wlan0".to_string(),
-            channels: vec![1, 6, 11],
             channel: 6,
-            last_odid_received: None,
-            channel_mod_freq_ms: 1000,
+            ..interface(transport)
         };
 
         assert_eq!(interface.channel, 6);
 
-        interface.adjust_channel();
+        interface.adjust_channel().unwrap();
 
         assert_eq!(interface.channel, 11);
 
-        interface.adjust_channel();
+        interface.adjust_channel().unwrap();
 
         assert_eq!(interface.channel, 1);
     }
+
+    #[test]
+    fn test_device_switcheroo_errors() {
+        // the driver stays on channel 6
+        let transport = MockTransport::default().tune("wlan0mon", 2437);
+
+        let mut interface = WifiInterface {
+            channel: 6,
+            ..interface(transport)
+        };
+
+        assert!(interface.adjust_channel().is_err());
+        assert_eq!(interface.channel, 11);
+    }
+
+    #[test]
+    fn test_set_channels() {
+        let mut interface = WifiInterface {
+            channel: 6,
+            ..interface(MockTransport::default())
+        };
+
+        assert!(!interface.set_channels(vec![6, 36]));
+        assert_eq!(interface.channel, 6);
+
+        interface.update_last_odid_received(Utc::now());
+
+        assert!(interface.set_channels(vec![1, 11]));
+        assert_eq!(interface.channel, 1);
+        assert_eq!(interface.last_odid_received, None);
+
+        assert!(!interface.set_channels(vec![]));
+        assert_eq!(interface.channels, vec![1, 11]);
+    }
+
+    #[test]
+    fn test_is_tracking() {
+        let mut interface = interface(MockTransport::default());
+
+        assert!(!interface.is_tracking());
+
+        interface.update_last_odid_received(Utc::now() - Duration::seconds(5));
+
+        assert!(interface.is_tracking());
+
+        interface.update_last_odid_received(Utc::now() - Duration::seconds(60));
+
+        assert!(!interface.is_tracking());
+    }
 }
@@ -1,13 +1,21 @@
+mod channel_plan;
 mod config;
-mod entity;
-mod frame;
+mod coordinator;
+mod hopping;
 mod interface;
+mod nl80211;
 mod repo;
+mod source;
 mod task;
 
+pub use channel_plan::*;
 pub use config::*;
-pub use entity::*;
-pub use frame::*;
+pub use coordinator::*;
+pub use hopping::*;
 pub use interface::*;
+pub use nl80211::*;
 pub use repo::*;
+pub use source::*;
 pub use task::*;
+
+pub use crate::odid::wifi::*;
@@ -0,0 +1,1166 @@
+use std::{
+    fmt, io, mem,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    sync::{Arc, Mutex},
+};
+
+use libc::c_char;
+use thiserror::Error;
+
+// generic netlink controller
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+const NL80211_CMD_GET_WIPHY: u8 = 1;
+const NL80211_CMD_SET_WIPHY: u8 = 2;
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_NEW_INTERFACE: u8 = 7;
+const NL80211_CMD_DEL_INTERFACE: u8 = 8;
+
+const NL80211_ATTR_WIPHY: u16 = 1;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFNAME: u16 = 4;
+const NL80211_ATTR_IFTYPE: u16 = 5;
+const NL80211_ATTR_WIPHY_BANDS: u16 = 22;
+const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+const NL80211_ATTR_CHANNEL_WIDTH: u16 = 159;
+const NL80211_ATTR_CENTER_FREQ1: u16 = 160;
+const NL80211_ATTR_SPLIT_WIPHY_DUMP: u16 = 174;
+
+const NL80211_BAND_ATTR_FREQS: u16 = 1;
+const NL80211_FREQUENCY_ATTR_FREQ: u16 = 1;
+const NL80211_FREQUENCY_ATTR_DISABLED: u16 = 2;
+
+const NLMSG_HEADER_LENGTH: usize = 16;
+const GENL_HEADER_LENGTH: usize = 4;
+const NLA_HEADER_LENGTH: usize = 4;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_ACK: u16 = 0x4;
+const NLM_F_DUMP: u16 = 0x300;
+// the nested and byte order flags share the attribute type field
+const NLA_TYPE_MASK: u16 = 0x3fff;
+
+#[derive(Error, Debug)]
+pub enum Nl80211Error {
+    #[error("StdError: {0}")]
+    StdError(#[from] std::io::Error),
+    #[error("nl80211 is not available, is a wireless driver loaded?")]
+    FamilyNotFound,
+    #[error("Permission denied, CAP_NET_ADMIN is required")]
+    PermissionDenied,
+    #[error("No wireless interface named {0}")]
+    InterfaceNotFound(String),
+    #[error("Channel {0} has no known frequency")]
+    UnknownChannel(u64),
+    #[error("Requested {requested} MHz but the interface is on {actual:?} MHz")]
+    ChannelMismatch { requested: u32, actual: Option<u32> },
+    #[error("Malformed netlink reply: {0}")]
+    MalformedReply(&'static str),
+    #[error("Kernel rejected the request: {0}")]
+    Kernel(std::io::Error),
+}
+
+impl Nl80211Error {
+    fn from_errno(errno: i32) -> Self {
+        match errno {
+            libc::EPERM | libc::EACCES => Nl80211Error::PermissionDenied,
+            _ => Nl80211Error::Kernel(io::Error::from_raw_os_error(errno)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub kind: u16,
+    pub payload: Vec<u8>,
+}
+
+impl Attribute {
+    pub fn u32(kind: u16, value: u32) -> Self {
+        Attribute {
+            kind,
+            payload: value.to_ne_bytes().to_vec(),
+        }
+    }
+
+    pub fn flag(kind: u16) -> Self {
+        Attribute {
+            kind,
+            payload: vec![],
+        }
+    }
+
+    pub fn nested(kind: u16, attributes: &[Attribute]) -> Self {
+        Attribute {
+            kind,
+            payload: encode_attributes(attributes),
+        }
+    }
+
+    // netlink strings are null terminated
+    pub fn string(kind: u16, value: &str) -> Self {
+        let mut payload = value.as_bytes().to_vec();
+        payload.push(0);
+
+        Attribute { kind, payload }
+    }
+
+    pub fn as_u16(&self) -> Option<u16> {
+        Some(u16::from_ne_bytes(self.payload.get(..2)?.try_into().ok()?))
+    }
+
+    pub fn as_u32(&self) -> Option<u32> {
+        Some(u32::from_ne_bytes(self.payload.get(..4)?.try_into().ok()?))
+    }
+
+    pub fn as_string(&self) -> Option<String> {
+        let text = self.payload.split(|b| *b == 0).next()?;
+
+        String::from_utf8(text.to_vec()).ok()
+    }
+
+    pub fn as_nested(&self) -> Result<Vec<Attribute>, Nl80211Error> {
+        decode_attributes(&self.payload)
+    }
+}
+
+/// A generic netlink message: the command and its attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenlMessage {
+    pub command: u8,
+    pub attributes: Vec<Attribute>,
+}
+
+impl GenlMessage {
+    pub fn new(command: u8, attributes: Vec<Attribute>) -> Self {
+        GenlMessage {
+            command,
+            attributes,
+        }
+    }
+
+    pub fn attribute(&self, kind: u16) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.kind == kind)
+    }
+}
+
+fn align(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+fn encode_attributes(attributes: &[Attribute]) -> Vec<u8> {
+    let mut buffer = vec![];
+
+    for attribute in attributes {
+        let length = NLA_HEADER_LENGTH + attribute.payload.len();
+
+        buffer.extend_from_slice(&(length as u16).to_ne_bytes());
+        buffer.extend_from_slice(&attribute.kind.to_ne_bytes());
+        buffer.extend_from_slice(&attribute.payload);
+        buffer.resize(align(buffer.len()), 0);
+    }
+
+    buffer
+}
+
+fn encode_request(family: u16, flags: u16, seq: u32, message: &GenlMessage) -> Vec<u8> {
+    let mut buffer = vec![0; NLMSG_HEADER_LENGTH + GENL_HEADER_LENGTH];
+
+    buffer[NLMSG_HEADER_LENGTH] = message.command;
+    buffer.extend_from_slice(&encode_attributes(&message.attributes));
+
+    let length = buffer.len() as u32;
+
+    buffer[0..4].copy_from_slice(&length.to_ne_bytes());
+    buffer[4..6].copy_from_slice(&family.to_ne_bytes());
+    buffer[6..8].copy_from_slice(&(flags | NLM_F_REQUEST).to_ne_bytes());
+    buffer[8..12].copy_from_slice(&seq.to_ne_bytes());
+
+    buffer
+}
+
+fn decode_attributes(mut input: &[u8]) -> Result<Vec<Attribute>, Nl80211Error> {
+    let mut attributes = vec![];
+
+    while input.len() >= NLA_HEADER_LENGTH {
+        let length = u16::from_ne_bytes([input[0], input[1]]) as usize;
+        let kind = u16::from_ne_bytes([input[2], input[3]]) & NLA_TYPE_MASK;
+
+        if length < NLA_HEADER_LENGTH || length > input.len() {
+            return Err(Nl80211Error::MalformedReply("attribute length"));
+        }
+
+        attributes.push(Attribute {
+            kind,
+            payload: input[NLA_HEADER_LENGTH..length].to_vec(),
+        });
+
+        input = &input[align(length).min(input.len())..];
+    }
+
+    Ok(attributes)
+}
+
+/// Messages of one receive buffer, and whether the reply is complete (an ack, or the end of a
+/// dump).
+fn decode_replies(mut input: &[u8], seq: u32) -> Result<(Vec<GenlMessage>, bool), Nl80211Error> {
+    let mut messages = vec![];
+
+    while input.len() >= NLMSG_HEADER_LENGTH {
+        let length = u32::from_ne_bytes(input[0..4].try_into().unwrap()) as usize;
+        let kind = u16::from_ne_bytes([input[4], input[5]]);
+        let message_seq = u32::from_ne_bytes(input[8..12].try_into().unwrap());
+
+        if length < NLMSG_HEADER_LENGTH || length > input.len() {
+            return Err(Nl80211Error::MalformedReply("message length"));
+        }
+
+        let payload = &input[NLMSG_HEADER_LENGTH..length];
+        input = &input[align(length).min(input.len())..];
+
+        if message_seq != seq {
+            continue;
+        }
+
+        match kind {
+            NLMSG_ERROR => {
+                let errno = payload
+                    .get(..4)
+                    .map(|errno| i32::from_ne_bytes(errno.try_into().unwrap()))
+                    .ok_or(Nl80211Error::MalformedReply("error message"))?;
+
+                if errno != 0 {
+                    return Err(Nl80211Error::from_errno(-errno));
+                }
+
+                return Ok((messages, true));
+            }
+            NLMSG_DONE => return Ok((messages, true)),
+            _ => {
+                if payload.len() < GENL_HEADER_LENGTH {
+                    return Err(Nl80211Error::MalformedReply("generic netlink header"));
+                }
+
+                messages.push(GenlMessage::new(
+                    payload[0],
+                    decode_attributes(&payload[GENL_HEADER_LENGTH..])?,
+                ));
+            }
+        }
+    }
+
+    Ok((messages, false))
+}
+
+/// The kernel side of the controller, mocked in tests.
+pub trait Nl80211Transport: Send {
+    /// Sends an nl80211 request and collects its replies. `dump` requests cover every object,
+    /// e.g. every interface.
+    fn request(
+        &mut self,
+        message: &GenlMessage,
+        dump: bool,
+    ) -> Result<Vec<GenlMessage>, Nl80211Error>;
+
+    fn set_link_up(&mut self, name: &str) -> Result<(), Nl80211Error>;
+}
+
+impl<T: Nl80211Transport + ?Sized> Nl80211Transport for Box<T> {
+    fn request(
+        &mut self,
+        message: &GenlMessage,
+        dump: bool,
+    ) -> Result<Vec<GenlMessage>, Nl80211Error> {
+        (**self).request(message, dump)
+    }
+
+    fn set_link_up(&mut self, name: &str) -> Result<(), Nl80211Error> {
+        (**self).set_link_up(name)
+    }
+}
+
+/// A generic netlink socket bound to the nl80211 family.
+pub struct NetlinkSocket {
+    fd: OwnedFd,
+    family: u16,
+    seq: u32,
+}
+
+impl NetlinkSocket {
+    const RECEIVE_BUFFER_SIZE: usize = 32 * 1024;
+
+    pub fn open() -> Result<Self, Nl80211Error> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_GENERIC,
+            )
+        };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+
+        let result = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+
+        if result < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut socket = NetlinkSocket {
+            fd,
+            family: GENL_ID_CTRL,
+            seq: 0,
+        };
+
+        let replies = socket
+            .send(
+                &GenlMessage::new(
+                    CTRL_CMD_GETFAMILY,
+                    vec![Attribute::string(CTRL_ATTR_FAMILY_NAME, "nl80211")],
+                ),
+                false,
+            )
+            .map_err(|e| match e {
+                Nl80211Error::Kernel(e) if e.raw_os_error() == Some(libc::ENOENT) => {
+                    Nl80211Error::FamilyNotFound
+                }
+                e => e,
+            })?;
+
+        socket.family = replies
+            .iter()
+            .find_map(|reply| reply.attribute(CTRL_ATTR_FAMILY_ID)?.as_u16())
+            .ok_or(Nl80211Error::FamilyNotFound)?;
+
+        Ok(socket)
+    }
+
+    fn send(
+        &mut self,
+        message: &GenlMessage,
+        dump: bool,
+    ) -> Result<Vec<GenlMessage>, Nl80211Error> {
+        self.seq = self.seq.wrapping_add(1);
+
+        let flags = if dump { NLM_F_DUMP } else { NLM_F_ACK };
+        let request = encode_request(self.family, flags, self.seq, message);
+
+        let sent = unsafe {
+            libc::send(
+                self.fd.as_raw_fd(),
+                request.as_ptr() as *const libc::c_void,
+                request.len(),
+                0,
+            )
+        };
+
+        if sent < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut buffer = vec![0u8; NetlinkSocket::RECEIVE_BUFFER_SIZE];
+        let mut messages = vec![];
+
+        loop {
+            let received = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                    0,
+                )
+            };
+
+            if received < 0 {
+                return Err(io::Error::last_os_error().into());
+            }
+
+            let (replies, done) = decode_replies(&buffer[..received as usize], self.seq)?;
+
+            messages.extend(replies);
+
+            if done {
+                return Ok(messages);
+            }
+        }
+    }
+}
+
+impl Nl80211Transport for NetlinkSocket {
+    fn request(
+        &mut self,
+        message: &GenlMessage,
+        dump: bool,
+    ) -> Result<Vec<GenlMessage>, Nl80211Error> {
+        self.send(message, dump)
+    }
+
+    fn set_link_up(&mut self, name: &str) -> Result<(), Nl80211Error> {
+        if name.len() >= libc::IFNAMSIZ {
+            return Err(Nl80211Error::InterfaceNotFound(name.to_string()));
+        }
+
+        let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut request: libc::ifreq = unsafe { mem::zeroed() };
+
+        for (c, b) in request.ifr_name.iter_mut().zip(name.bytes()) {
+            *c = b as c_char;
+        }
+
+        if unsafe { libc::ioctl(fd.as_raw_fd(), libc::SIOCGIFFLAGS as _, &mut request) } < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        unsafe {
+            request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+        }
+
+        if unsafe { libc::ioctl(fd.as_raw_fd(), libc::SIOCSIFFLAGS as _, &request) } < 0 {
+            let e = io::Error::last_os_error();
+
+            return Err(match e.raw_os_error() {
+                Some(errno) => Nl80211Error::from_errno(errno),
+                None => e.into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceType {
+    Station,
+    AccessPoint,
+    Monitor,
+    Other(u32),
+}
+
+impl From<u32> for InterfaceType {
+    fn from(value: u32) -> Self {
+        match value {
+            2 => InterfaceType::Station,
+            3 => InterfaceType::AccessPoint,
+            6 => InterfaceType::Monitor,
+            _ => InterfaceType::Other(value),
+        }
+    }
+}
+
+impl From<InterfaceType> for u32 {
+    fn from(value: InterfaceType) -> Self {
+        match value {
+            InterfaceType::Station => 2,
+            InterfaceType::AccessPoint => 3,
+            InterfaceType::Monitor => 6,
+            InterfaceType::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelWidth {
+    Mhz20NoHt,
+    Mhz20,
+    Mhz40,
+    Mhz80,
+    Mhz80P80,
+    Mhz160,
+    Other(u32),
+}
+
+impl From<u32> for ChannelWidth {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => ChannelWidth::Mhz20NoHt,
+            1 => ChannelWidth::Mhz20,
+            2 => ChannelWidth::Mhz40,
+            3 => ChannelWidth::Mhz80,
+            4 => ChannelWidth::Mhz80P80,
+            5 => ChannelWidth::Mhz160,
+            _ => ChannelWidth::Other(value),
+        }
+    }
+}
+
+impl From<ChannelWidth> for u32 {
+    fn from(value: ChannelWidth) -> Self {
+        match value {
+            ChannelWidth::Mhz20NoHt => 0,
+            ChannelWidth::Mhz20 => 1,
+            ChannelWidth::Mhz40 => 2,
+            ChannelWidth::Mhz80 => 3,
+            ChannelWidth::Mhz80P80 => 4,
+            ChannelWidth::Mhz160 => 5,
+            ChannelWidth::Other(value) => value,
+        }
+    }
+}
+
+/// A channel as nl80211 describes it: the primary frequency, the width, and the center of
+/// the whole (possibly bonded) channel, all in MHz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Channel {
+    pub frequency: u32,
+    pub width: ChannelWidth,
+    pub center_frequency: u32,
+}
+
+impl Channel {
+    /// A 20 MHz channel without HT, which every monitor capable driver supports.
+    pub fn new(frequency: u32) -> Self {
+        Channel {
+            frequency,
+            width: ChannelWidth::Mhz20NoHt,
+            center_frequency: frequency,
+        }
+    }
+}
+
+/// Center frequency in MHz of a 2.4 or 5 GHz channel number.
+pub fn channel_to_frequency(channel: u64) -> Option<u32> {
+    match channel {
+        1..=13 => Some(2407 + 5 * channel as u32),
+        14 => Some(2484),
+        32..=177 => Some(5000 + 5 * channel as u32),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceState {
+    pub ifindex: u32,
+    pub name: String,
+    pub wiphy: u32,
+    pub interface_type: InterfaceType,
+    pub channel: Option<Channel>,
+}
+
+impl TryFrom<&GenlMessage> for InterfaceState {
+    type Error = Nl80211Error;
+
+    fn try_from(message: &GenlMessage) -> Result<Self, Self::Error> {
+        let u32_attribute = |kind| message.attribute(kind).and_then(Attribute::as_u32);
+
+        let channel = u32_attribute(NL80211_ATTR_WIPHY_FREQ).map(|frequency| Channel {
+            frequency,
+            width: u32_attribute(NL80211_ATTR_CHANNEL_WIDTH)
+                .map(ChannelWidth::from)
+                .unwrap_or(ChannelWidth::Mhz20NoHt),
+            center_frequency: u32_attribute(NL80211_ATTR_CENTER_FREQ1).unwrap_or(frequency),
+        });
+
+        Ok(InterfaceState {
+            ifindex: u32_attribute(NL80211_ATTR_IFINDEX)
+                .ok_or(Nl80211Error::MalformedReply("interface index"))?,
+            name: message
+                .attribute(NL80211_ATTR_IFNAME)
+                .and_then(Attribute::as_string)
+                .ok_or(Nl80211Error::MalformedReply("interface name"))?,
+            wiphy: u32_attribute(NL80211_ATTR_WIPHY)
+                .ok_or(Nl80211Error::MalformedReply("wiphy"))?,
+            interface_type: u32_attribute(NL80211_ATTR_IFTYPE)
+                .map(InterfaceType::from)
+                .ok_or(Nl80211Error::MalformedReply("interface type"))?,
+            channel,
+        })
+    }
+}
+
+/// Monitor interface and channel control over nl80211.
+pub struct Nl80211Controller<T: Nl80211Transport = NetlinkSocket> {
+    transport: T,
+}
+
+/// One controller for every adapter, so hopping reuses a socket instead of opening one per
+/// hop, and tests can swap the kernel for a mock.
+pub type SharedController = Arc<Mutex<Nl80211Controller<Box<dyn Nl80211Transport>>>>;
+
+impl<T: Nl80211Transport> fmt::Debug for Nl80211Controller<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nl80211Controller").finish_non_exhaustive()
+    }
+}
+
+impl Nl80211Controller<NetlinkSocket> {
+    pub fn open() -> Result<Self, Nl80211Error> {
+        Ok(Nl80211Controller::new(NetlinkSocket::open()?))
+    }
+
+    pub fn open_shared() -> Result<SharedController, Nl80211Error> {
+        Ok(Nl80211Controller::shared(NetlinkSocket::open()?))
+    }
+}
+
+impl Nl80211Controller<Box<dyn Nl80211Transport>> {
+    pub fn shared(transport: impl Nl80211Transport + 'static) -> SharedController {
+        Arc::new(Mutex::new(Nl80211Controller::new(Box::new(transport))))
+    }
+}
+
+impl<T: Nl80211Transport> Nl80211Controller<T> {
+    // interface names are limited to IFNAMSIZ - 1 bytes
+    const MAX_INTERFACE_NAME_LENGTH: usize = 15;
+
+    pub fn new(transport: T) -> Self {
+        Nl80211Controller { transport }
+    }
+
+    pub fn interfaces(&mut self) -> Result<Vec<InterfaceState>, Nl80211Error> {
+        self.transport
+            .request(&GenlMessage::new(NL80211_CMD_GET_INTERFACE, vec![]), true)?
+            .iter()
+            .map(InterfaceState::try_from)
+            .collect()
+    }
+
+    pub fn interface(&mut self, name: &str) -> Result<InterfaceState, Nl80211Error> {
+        self.interfaces()?
+            .into_iter()
+            .find(|interface| interface.name == name)
+            .ok_or_else(|| Nl80211Error::InterfaceNotFound(name.to_string()))
+    }
+
+    /// Adds a monitor interface named `name` on `wiphy` and brings it up.
+    pub fn create_monitor(
+        &mut self,
+        wiphy: u32,
+        name: &str,
+    ) -> Result<InterfaceState, Nl80211Error> {
+        let replies = self.transport.request(
+            &GenlMessage::new(
+                NL80211_CMD_NEW_INTERFACE,
+                vec![
+                    Attribute::u32(NL80211_ATTR_WIPHY, wiphy),
+                    Attribute::string(NL80211_ATTR_IFNAME, name),
+                    Attribute::u32(NL80211_ATTR_IFTYPE, InterfaceType::Monitor.into()),
+                ],
+            ),
+            false,
+        )?;
+
+        let interface = match replies.first() {
+            Some(reply) => InterfaceState::try_from(reply)?,
+            None => self.interface(name)?,
+        };
+
+        self.transport.set_link_up(&interface.name)?;
+
+        Ok(interface)
+    }
+
+    pub fn delete_interface(&mut self, ifindex: u32) -> Result<(), Nl80211Error> {
+        self.transport.request(
+            &GenlMessage::new(
+                NL80211_CMD_DEL_INTERFACE,
+                vec![Attribute::u32(NL80211_ATTR_IFINDEX, ifindex)],
+            ),
+            false,
+        )?;
+
+        Ok(())
+    }
+
+    /// The monitor interface to capture on for `device`: the device itself when it already is
+    /// one, otherwise a `<device>mon` interface on the same wiphy, created when missing.
+    pub fn enable_monitor(&mut self, device: &str) -> Result<InterfaceState, Nl80211Error> {
+        let interfaces = self.interfaces()?;
+
+        let interface = interfaces
+            .iter()
+            .find(|interface| interface.name == device)
+            .ok_or_else(|| Nl80211Error::InterfaceNotFound(device.to_string()))?;
+
+        if interface.interface_type == InterfaceType::Monitor {
+            self.transport.set_link_up(&interface.name)?;
+
+            return Ok(interface.clone());
+        }
+
+        let mut monitor_name = format!("{}mon", device);
+        monitor_name.truncate(Self::MAX_INTERFACE_NAME_LENGTH);
+
+        let existing_monitor = interfaces.iter().find(|other| {
+            other.wiphy == interface.wiphy && other.interface_type == InterfaceType::Monitor
+        });
+
+        match existing_monitor {
+            Some(monitor) => {
+                self.transport.set_link_up(&monitor.name)?;
+
+                Ok(monitor.clone())
+            }
+            None => self.create_monitor(interface.wiphy, &monitor_name),
+        }
+    }
+
+    /// Frequencies in MHz the interface's wiphy can tune to, leaving out the ones disabled by
+    /// the regulatory domain.
+    pub fn supported_frequencies(&mut self, name: &str) -> Result<Vec<u32>, Nl80211Error> {
+        let interface = self.interface(name)?;
+
+        // a split dump spreads the bands over several messages
+        let replies = self.transport.request(
+            &GenlMessage::new(
+                NL80211_CMD_GET_WIPHY,
+                vec![
+                    Attribute::u32(NL80211_ATTR_WIPHY, interface.wiphy),
+                    Attribute::flag(NL80211_ATTR_SPLIT_WIPHY_DUMP),
+                ],
+            ),
+            true,
+        )?;
+
+        let mut frequencies = vec![];
+
+        for reply in &replies {
+            let Some(bands) = reply.attribute(NL80211_ATTR_WIPHY_BANDS) else {
+                continue;
+            };
+
+            for band in bands.as_nested()? {
+                for band_attribute in band.as_nested()? {
+                    if band_attribute.kind != NL80211_BAND_ATTR_FREQS {
+                        continue;
+                    }
+
+                    for frequency in band_attribute.as_nested()? {
+                        let frequency = frequency.as_nested()?;
+                        let disabled = frequency
+                            .iter()
+                            .any(|attribute| attribute.kind == NL80211_FREQUENCY_ATTR_DISABLED);
+
+                        let value = frequency
+                            .iter()
+                            .find(|attribute| attribute.kind == NL80211_FREQUENCY_ATTR_FREQ)
+                            .and_then(Attribute::as_u32)
+                            .ok_or(Nl80211Error::MalformedReply("frequency"))?;
+
+                        if !disabled && !frequencies.contains(&value) {
+                            frequencies.push(value);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(frequencies)
+    }
+
+    /// Tunes the interface's wiphy to `channel`, and checks the interface reports it back.
+    pub fn set_channel(
+        &mut self,
+        name: &str,
+        channel: Channel,
+    ) -> Result<InterfaceState, Nl80211Error> {
+        let interface = self.interface(name)?;
+
+        self.transport.request(
+            &GenlMessage::new(
+                NL80211_CMD_SET_WIPHY,
+                vec![
+                    Attribute::u32(NL80211_ATTR_IFINDEX, interface.ifindex),
+                    Attribute::u32(NL80211_ATTR_WIPHY_FREQ, channel.frequency),
+                    Attribute::u32(NL80211_ATTR_CHANNEL_WIDTH, channel.width.into()),
+                    Attribute::u32(NL80211_ATTR_CENTER_FREQ1, channel.center_frequency),
+                ],
+            ),
+            false,
+        )?;
+
+        let interface = self.interface(name)?;
+        let actual = interface.channel.map(|channel| channel.frequency);
+
+        if actual != Some(channel.frequency) {
+            return Err(Nl80211Error::ChannelMismatch {
+                requested: channel.frequency,
+                actual,
+            });
+        }
+
+        Ok(interface)
+    }
+}
+
+/// A scripted kernel for tests of the controller and the adapters driving it.
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    #[derive(Default)]
+    pub struct MockTransport {
+        pub requests: Vec<(GenlMessage, bool)>,
+        pub replies: VecDeque<Result<Vec<GenlMessage>, Nl80211Error>>,
+        pub links_up: Vec<String>,
+    }
+
+    impl MockTransport {
+        pub fn reply(mut self, reply: Result<Vec<GenlMessage>, Nl80211Error>) -> Self {
+            self.replies.push_back(reply);
+            self
+        }
+
+        /// Replies to one `set_channel` on `name`, which ends up on `frequency`.
+        pub fn tune(self, name: &str, frequency: u32) -> Self {
+            self.reply(Ok(vec![interface_message(
+                4,
+                name,
+                InterfaceType::Monitor,
+                None,
+            )]))
+            .reply(Ok(vec![]))
+            .reply(Ok(vec![interface_message(
+                4,
+                name,
+                InterfaceType::Monitor,
+                Some(frequency),
+            )]))
+        }
+    }
+
+    impl Nl80211Transport for MockTransport {
+        fn request(
+            &mut self,
+            message: &GenlMessage,
+            dump: bool,
+        ) -> Result<Vec<GenlMessage>, Nl80211Error> {
+            self.requests.push((message.clone(), dump));
+            self.replies.pop_front().expect("Unexpected request")
+        }
+
+        fn set_link_up(&mut self, name: &str) -> Result<(), Nl80211Error> {
+            self.links_up.push(name.to_string());
+            Ok(())
+        }
+    }
+
+    pub fn interface_message(
+        ifindex: u32,
+        name: &str,
+        interface_type: InterfaceType,
+        frequency: Option<u32>,
+    ) -> GenlMessage {
+        let mut attributes = vec![
+            Attribute::u32(NL80211_ATTR_IFINDEX, ifindex),
+            Attribute::string(NL80211_ATTR_IFNAME, name),
+            Attribute::u32(NL80211_ATTR_WIPHY, 0),
+            Attribute::u32(NL80211_ATTR_IFTYPE, interface_type.into()),
+        ];
+
+        if let Some(frequency) = frequency {
+            attributes.push(Attribute::u32(NL80211_ATTR_WIPHY_FREQ, frequency));
+            attributes.push(Attribute::u32(NL80211_ATTR_CHANNEL_WIDTH, 0));
+            attributes.push(Attribute::u32(NL80211_ATTR_CENTER_FREQ1, frequency));
+        }
+
+        GenlMessage::new(NL80211_CMD_NEW_INTERFACE, attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{interface_message, MockTransport};
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let message = GenlMessage::new(
+            NL80211_CMD_NEW_INTERFACE,
+            vec![
+                Attribute::u32(NL80211_ATTR_WIPHY, 1),
+                // 6 byte payload, padded to 8
+                Attribute::string(NL80211_ATTR_IFNAME, "wlan0"),
+                Attribute::u32(NL80211_ATTR_IFTYPE, 6),
+            ],
+        );
+
+        let request = encode_request(0x1c, NLM_F_ACK, 7, &message);
+
+        assert_eq!(request.len() % 4, 0);
+        assert_eq!(
+            u32::from_ne_bytes(request[0..4].try_into().unwrap()) as usize,
+            request.len()
+        );
+        assert_eq!(
+            u16::from_ne_bytes([request[6], request[7]]),
+            NLM_F_REQUEST | NLM_F_ACK
+        );
+
+        let (messages, done) = decode_replies(&request, 7).unwrap();
+
+        assert!(!done);
+        assert_eq!(messages, vec![message]);
+        assert_eq!(
+            messages[0]
+                .attribute(NL80211_ATTR_IFNAME)
+                .unwrap()
+                .as_string()
+                .unwrap(),
+            "wlan0"
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut ack = encode_request(NLMSG_ERROR, 0, 3, &GenlMessage::new(0, vec![]));
+        ack[16..20].copy_from_slice(&0i32.to_ne_bytes());
+
+        assert_eq!(decode_replies(&ack, 3).unwrap(), (vec![], true));
+
+        ack[16..20].copy_from_slice(&(-libc::EPERM).to_ne_bytes());
+
+        assert!(matches!(
+            decode_replies(&ack, 3),
+            Err(Nl80211Error::PermissionDenied)
+        ));
+
+        ack[16..20].copy_from_slice(&(-libc::EBUSY).to_ne_bytes());
+
+        assert!(matches!(
+            decode_replies(&ack, 3),
+            Err(Nl80211Error::Kernel(e)) if e.raw_os_error() == Some(libc::EBUSY)
+        ));
+
+        // replies to other requests are ignored
+        assert_eq!(decode_replies(&ack, 4).unwrap(), (vec![], false));
+
+        assert!(matches!(decode_replies(&ack[..10], 3), Ok((_, false))));
+        ack[0..4].copy_from_slice(&64u32.to_ne_bytes());
+        assert!(matches!(
+            decode_replies(&ack, 3),
+            Err(Nl80211Error::MalformedReply(_))
+        ));
+    }
+
+    #[test]
+    fn test_channel_to_frequency() {
+        assert_eq!(channel_to_frequency(1), Some(2412));
+        assert_eq!(channel_to_frequency(6), Some(2437));
+        assert_eq!(channel_to_frequency(14), Some(2484));
+        assert_eq!(channel_to_frequency(149), Some(5745));
+        assert_eq!(channel_to_frequency(0), None);
+    }
+
+    fn frequency(frequency: u32, disabled: bool) -> Attribute {
+        let mut attributes = vec![Attribute::u32(NL80211_FREQUENCY_ATTR_FREQ, frequency)];
+
+        if disabled {
+            attributes.push(Attribute::flag(NL80211_FREQUENCY_ATTR_DISABLED));
+        }
+
+        Attribute::nested(0, &attributes)
+    }
+
+    fn band(index: u16, frequencies: &[Attribute]) -> Attribute {
+        Attribute::nested(
+            index,
+            &[Attribute::nested(NL80211_BAND_ATTR_FREQS, frequencies)],
+        )
+    }
+
+    #[test]
+    fn test_supported_frequencies() {
+        let transport = MockTransport::default()
+            .reply(Ok(vec![interface_message(
+                4,
+                "wlan0mon",
+                InterfaceType::Monitor,
+                None,
+            )]))
+            .reply(Ok(vec![
+                // the first message of a split dump carries no bands
+                GenlMessage::new(
+                    NL80211_CMD_GET_WIPHY,
+                    vec![Attribute::u32(NL80211_ATTR_WIPHY, 0)],
+                ),
+                GenlMessage::new(
+                    NL80211_CMD_GET_WIPHY,
+                    vec![Attribute::nested(
+                        NL80211_ATTR_WIPHY_BANDS,
+                        &[band(0, &[frequency(2412, false), frequency(2437, false)])],
+                    )],
+                ),
+                GenlMessage::new(
+                    NL80211_CMD_GET_WIPHY,
+                    vec![Attribute::nested(
+                        NL80211_ATTR_WIPHY_BANDS,
+                        &[band(1, &[frequency(5180, false), frequency(5745, true)])],
+                    )],
+                ),
+            ]));
+
+        let mut controller = Nl80211Controller::new(transport);
+
+        assert_eq!(
+            controller.supported_frequencies("wlan0mon").unwrap(),
+            vec![2412, 2437, 5180]
+        );
+
+        let (request, dump) = &controller.transport.requests[1];
+
+        assert!(dump);
+        assert_eq!(request.command, NL80211_CMD_GET_WIPHY);
+        assert!(request.attribute(NL80211_ATTR_SPLIT_WIPHY_DUMP).is_some());
+    }
+
+    #[test]
+    fn test_enable_monitor_creates_interface() {
+        let transport = MockTransport::default()
+            .reply(Ok(vec![interface_message(
+                3,
+                "wlan0",
+                InterfaceType::Station,
+                None,
+            )]))
+            .reply(Ok(vec![interface_message(
+                4,
+                "wlan0mon",
+                InterfaceType::Monitor,
+                None,
+            )]));
+
+        let mut controller = Nl80211Controller::new(transport);
+        let monitor = controller.enable_monitor("wlan0").unwrap();
+
+        assert_eq!(monitor.name, "wlan0mon");
+        assert_eq!(monitor.interface_type, InterfaceType::Monitor);
+
+        let transport = controller.transport;
+
+        assert_eq!(transport.requests.len(), 2);
+        assert_eq!(
+            transport.requests[0],
+            (GenlMessage::new(NL80211_CMD_GET_INTERFACE, vec![]), true)
+        );
+        assert_eq!(
+            transport.requests[1].0,
+            GenlMessage::new(
+                NL80211_CMD_NEW_INTERFACE,
+                vec![
+                    Attribute::u32(NL80211_ATTR_WIPHY, 0),
+                    Attribute::string(NL80211_ATTR_IFNAME, "wlan0mon"),
+                    Attribute::u32(NL80211_ATTR_IFTYPE, 6),
+                ],
+            )
+        );
+        assert_eq!(transport.links_up, vec!["wlan0mon"]);
+    }
+
+    #[test]
+    fn test_enable_monitor_reuses_interface() {
+        let transport = MockTransport::default().reply(Ok(vec![
+            interface_message(3, "wlan0", InterfaceType::Station, None),
+            interface_message(4, "mon0", InterfaceType::Monitor, Some(2437)),
+        ]));
+
+        let mut controller = Nl80211Controller::new(transport);
+
+        assert_eq!(controller.enable_monitor("wlan0").unwrap().name, "mon0");
+        assert_eq!(controller.transport.links_up, vec!["mon0"]);
+
+        let transport = MockTransport::default().reply(Ok(vec![]));
+        let mut controller = Nl80211Controller::new(transport);
+
+        assert!(matches!(
+            controller.enable_monitor("wlan1"),
+            Err(Nl80211Error::InterfaceNotFound(name)) if name == "wlan1"
+        ));
+    }
+
+    #[test]
+    fn test_set_channel() {
+        let transport = MockTransport::default()
+            .reply(Ok(vec![interface_message(
+                4,
+                "wlan0mon",
+                InterfaceType::Monitor,
+                Some(2412),
+            )]))
+            .reply(Ok(vec![]))
+            .reply(Ok(vec![interface_message(
+                4,
+                "wlan0mon",
+                InterfaceType::Monitor,
+                Some(2437),
+            )]));
+
+        let mut controller = Nl80211Controller::new(transport);
+        let interface = controller
+            .set_channel("wlan0mon", Channel::new(2437))
+            .unwrap();
+
+        assert_eq!(interface.channel, Some(Channel::new(2437)));
+        assert_eq!(
+            controller.transport.requests[1].0,
+            GenlMessage::new(
+                NL80211_CMD_SET_WIPHY,
+                vec![
+                    Attribute::u32(NL80211_ATTR_IFINDEX, 4),
+                    Attribute::u32(NL80211_ATTR_WIPHY_FREQ, 2437),
+                    Attribute::u32(NL80211_ATTR_CHANNEL_WIDTH, 0),
+                    Attribute::u32(NL80211_ATTR_CENTER_FREQ1, 2437),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_set_channel_errors() {
+        let interface = interface_message(4, "wlan0mon", InterfaceType::Monitor, Some(2412));
+
+        // the driver accepted the request but stayed on its channel
+        let transport = MockTransport::default()
+            .reply(Ok(vec![interface.clone()]))
+            .reply(Ok(vec![]))
+            .reply(Ok(vec![interface.clone()]));
+
+        let mut controller = Nl80211Controller::new(transport);
+
+        assert!(matches!(
+            controller.set_channel("wlan0mon", Channel::new(2437)),
+            Err(Nl80211Error::ChannelMismatch {
+                requested: 2437,
+                actual: Some(2412)
+            })
+        ));
+
+        let transport = MockTransport::default()
+            .reply(Ok(vec![interface]))
+            .reply(Err(Nl80211Error::from_errno(libc::EBUSY)));
+
+        let mut controller = Nl80211Controller::new(transport);
+
+        assert!(matches!(
+            controller.set_channel("wlan0mon", Channel::new(2437)),
+            Err(Nl80211Error::Kernel(e)) if e.raw_os_error() == Some(libc::EBUSY)
+        ));
+    }
+}
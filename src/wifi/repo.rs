@@ -1,249 +1,496 @@
-use log::debug;
-use nom::bytes::complete::take;
-use nom::error::{context, ParseError, VerboseError};
-use nom::number::complete::{le_u16, le_u8};
-use nom::{IResult, InputTake};
 use radiotap::Radiotap;
-use std::convert::TryInto;
+use serde::{Deserialize, Serialize};
+
+use super::LinkType;
+use crate::odid::{ParseError, ParseErrorReason, ParseLayer};
+
+const FCS_LENGTH: usize = 4;
+
+const PPI_HEADER_LENGTH: usize = 8;
+const PPI_FIELD_HEADER_LENGTH: usize = 4;
+const PPI_80211_COMMON: u16 = 2;
+const PPI_80211_COMMON_LENGTH: usize = 20;
+const PPI_FLAG_ALIGNED: u8 = 0x1;
+const PPI_COMMON_FLAG_FCS: u16 = 0x1;
+const PPI_COMMON_FLAG_TSFT_MS: u16 = 0x2;
+const PPI_COMMON_FLAG_BAD_FCS: u16 = 0x4;
+const DLT_IEEE802_11: u32 = 105;
+
+/// What happens to a frame's trailing FCS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FcsMode {
+    /// Remove it, keeping every frame.
+    #[default]
+    Strip,
+    /// Remove it, dropping frames it doesn't match or the driver flagged as bad.
+    Verify,
+}
+
+/// How a frame was received, from its radiotap header. Fields the driver doesn't report are
+/// `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RadioMetadata {
+    /// Signal strength (RSSI) and noise in dBm.
+    pub signal: Option<i8>,
+    pub noise: Option<i8>,
+    /// Frequency in MHz.
+    pub frequency: Option<u16>,
+    /// The radiotap flags field, see `has_fcs` and `bad_fcs`.
+    pub flags: Option<u8>,
+    pub antenna: Option<u8>,
+    /// The adapter's TSF timer in microseconds when the frame's first bit arrived.
+    pub tsft: Option<u64>,
+}
+
+impl RadioMetadata {
+    const FLAG_FCS: u8 = 0x10;
+    const FLAG_BAD_FCS: u8 = 0x40;
+
+    /// Whether the frame ends with its 4 byte FCS.
+    pub fn has_fcs(&self) -> bool {
+        self.flags
+            .is_some_and(|flags| flags & RadioMetadata::FLAG_FCS != 0)
+    }
 
-use crate::wifi::{ASDSTAN_OUI, WIFI_ALLIANCE_OUI};
+    /// Whether the frame failed the FCS check.
+    pub fn bad_fcs(&self) -> bool {
+        self.flags
+            .is_some_and(|flags| flags & RadioMetadata::FLAG_BAD_FCS != 0)
+    }
+}
 
-use super::{
-    WifiActionFrame as ActionFrame, WifiBeaconFrame, WifiOpenDroneIDMessage as OpenDroneIDMessage,
-    WifiOpenDroneIDMessagePack as OpenDroneIDMessagePack,
-    WifiServiceDescriptorAttribute as ServiceDescriptorAttribute,
-};
+impl From<&Radiotap> for RadioMetadata {
+    fn from(radiotap: &Radiotap) -> Self {
+        let flags = radiotap.flags.as_ref().map(|flags| {
+            [
+                flags.cfp,
+                flags.preamble,
+                flags.wep,
+                flags.fragmentation,
+                flags.fcs,
+                flags.data_pad,
+                flags.bad_fcs,
+                flags.sgi,
+            ]
+            .iter()
+            .enumerate()
+            .fold(0u8, |bits, (bit, set)| bits | (u8::from(*set) << bit))
+        });
 
-pub async fn parse_open_drone_id_message_pack(
+        RadioMetadata {
+            signal: radiotap.antenna_signal.as_ref().map(|signal| signal.value),
+            noise: radiotap.antenna_noise.as_ref().map(|noise| noise.value),
+            frequency: radiotap.channel.as_ref().map(|channel| channel.freq),
+            flags,
+            antenna: radiotap.antenna.as_ref().map(|antenna| antenna.value),
+            tsft: radiotap.tsft.as_ref().map(|tsft| tsft.value),
+        }
+    }
+}
+
+/// The CRC-32 802.11 uses for its FCS.
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(0xffff_ffff, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Whether `frame` ends with a valid FCS.
+pub fn fcs_matches(frame: &[u8]) -> bool {
+    if frame.len() < FCS_LENGTH {
+        return false;
+    }
+
+    let (body, fcs) = frame.split_at(frame.len() - FCS_LENGTH);
+
+    crc32(body) == u32::from_le_bytes(fcs.try_into().unwrap())
+}
+
+/// The 802.11 frame of a captured frame of `link_type` without its FCS, and its radio
+/// metadata. Raw 802.11 doesn't say whether it carries an FCS, it is removed when it matches.
+pub fn strip_link_header(
     input: &[u8],
-) -> IResult<&[u8], OpenDroneIDMessagePack> {
-    let (input, message_type_and_version_pack) = le_u8(input)?;
+    link_type: LinkType,
+    fcs_mode: FcsMode,
+) -> Result<(RadioMetadata, &[u8]), ParseError> {
+    let (radio, frame, has_fcs) = match link_type {
+        LinkType::Ieee80211Radiotap => {
+            let (radio, frame) = parse_radiotap_header(input)?;
+            (radio, frame, Some(radio.has_fcs()))
+        }
+        LinkType::Ppi => {
+            let (radio, frame) = parse_ppi_header(input)?;
+            (radio, frame, Some(radio.has_fcs()))
+        }
+        LinkType::Ieee80211 => (RadioMetadata::default(), input, None),
+        LinkType::Other(_) => {
+            return Err(ParseError::new(
+                ParseLayer::Ieee80211,
+                0,
+                ParseErrorReason::UnexpectedType,
+            ))
+        }
+    };
 
-    debug!(
-        "Parsed message type and version pack: {}",
-        message_type_and_version_pack
+    let bad_fcs = ParseError::new(
+        ParseLayer::Ieee80211,
+        frame.len().saturating_sub(FCS_LENGTH),
+        ParseErrorReason::InvalidValue,
     );
 
-    let message_pack_type = message_type_and_version_pack >> 4;
-    let version_pack = message_type_and_version_pack & 0x0F;
+    if fcs_mode == FcsMode::Verify && radio.bad_fcs() {
+        return Err(bad_fcs);
+    }
 
-    debug!(
-        "Parsed message pack type: {}, version: {}",
-        message_pack_type, version_pack
-    );
+    match has_fcs {
+        Some(true) => {
+            if frame.len() < FCS_LENGTH {
+                return Err(ParseError::new(
+                    ParseLayer::Ieee80211,
+                    frame.len(),
+                    ParseErrorReason::TooShort,
+                ));
+            }
 
-    let (input, single_msg_size) = le_u8(input)?;
+            if fcs_mode == FcsMode::Verify && !fcs_matches(frame) {
+                return Err(bad_fcs);
+            }
 
-    debug!("Parsed single message size: {}", single_msg_size);
+            Ok((radio, &frame[..frame.len() - FCS_LENGTH]))
+        }
+        None if fcs_matches(frame) => Ok((radio, &frame[..frame.len() - FCS_LENGTH])),
+        _ => Ok((radio, frame)),
+    }
+}
 
-    let (mut input, num_messages) = le_u8(input)?;
+/// The radio metadata of a frame captured with a PPI header, and the 802.11 frame.
+pub fn parse_ppi_header(input: &[u8]) -> Result<(RadioMetadata, &[u8]), ParseError> {
+    let error = |offset, reason| ParseError::new(ParseLayer::Ppi, offset, reason);
 
-    debug!("Parsed number of messages: {}", num_messages);
+    if input.len() < PPI_HEADER_LENGTH {
+        return Err(error(input.len(), ParseErrorReason::TooShort));
+    }
 
-    let mut messages = Vec::new();
+    if input[0] != 0 {
+        return Err(error(0, ParseErrorReason::UnexpectedType));
+    }
 
-    debug!(
-        "Parsed message pack type: {}, version: {}, single_msg_size: {}",
-        message_pack_type, version_pack, single_msg_size
-    );
+    let aligned = input[1] & PPI_FLAG_ALIGNED != 0;
+    let length = u16::from_le_bytes([input[2], input[3]]) as usize;
+    let dlt = u32::from_le_bytes(input[4..8].try_into().unwrap());
 
-    for _ in 1..=num_messages {
-        let new_input = input;
+    if length < PPI_HEADER_LENGTH || length > input.len() {
+        return Err(error(2, ParseErrorReason::InvalidLength));
+    }
 
-        let (new_input, message_type_and_version) = le_u8(new_input)?;
+    if dlt != DLT_IEEE802_11 {
+        return Err(error(4, ParseErrorReason::UnexpectedType));
+    }
 
-        debug!(
-            "Parsed message type: {}, version: {}",
-            message_type_and_version >> 4,
-            message_type_and_version & 0x0F
-        );
+    let mut radio = RadioMetadata::default();
+    let mut offset = PPI_HEADER_LENGTH;
 
-        let message_type = message_type_and_version >> 4;
-        let version = message_type_and_version & 0x0F;
+    while offset + PPI_FIELD_HEADER_LENGTH <= length {
+        let kind = u16::from_le_bytes([input[offset], input[offset + 1]]);
+        let field_length = u16::from_le_bytes([input[offset + 2], input[offset + 3]]) as usize;
+        let start = offset + PPI_FIELD_HEADER_LENGTH;
 
-        let (new_input, message_body) = take(24usize)(new_input)?;
+        if start + field_length > length {
+            return Err(error(offset + 2, ParseErrorReason::InvalidLength));
+        }
 
-        debug!("Parsed message body: {:?}", message_body);
+        let field = &input[start..start + field_length];
 
-        messages.push(OpenDroneIDMessage {
-            message_type,
-            version,
-            message_body: message_body.try_into().unwrap(),
-        });
+        if kind == PPI_80211_COMMON && field_length >= PPI_80211_COMMON_LENGTH {
+            radio = ppi_80211_common(field);
+        }
 
-        input = new_input;
+        offset = start + field_length;
+
+        if aligned {
+            offset = (offset + 3) & !3;
+        }
     }
 
-    println!("Parsed {} messages", messages.len());
+    Ok((radio, &input[length..]))
+}
+
+fn ppi_80211_common(field: &[u8]) -> RadioMetadata {
+    let tsft = u64::from_le_bytes(field[0..8].try_into().unwrap());
+    let flags = u16::from_le_bytes([field[8], field[9]]);
+    let frequency = u16::from_le_bytes([field[12], field[13]]);
+
+    let mut radiotap_flags = 0;
 
-    Ok((
-        input,
-        OpenDroneIDMessagePack {
-            message_type: message_pack_type,
-            version: version_pack,
-            single_msg_size,
-            num_messages,
-            messages,
-        },
-    ))
+    if flags & PPI_COMMON_FLAG_FCS != 0 {
+        radiotap_flags |= RadioMetadata::FLAG_FCS;
+    }
+
+    if flags & PPI_COMMON_FLAG_BAD_FCS != 0 {
+        radiotap_flags |= RadioMetadata::FLAG_BAD_FCS;
+    }
+
+    RadioMetadata {
+        signal: Some(field[18] as i8),
+        noise: Some(field[19] as i8),
+        // zero when unknown
+        frequency: (frequency != 0).then_some(frequency),
+        flags: Some(radiotap_flags),
+        antenna: None,
+        tsft: Some(if flags & PPI_COMMON_FLAG_TSFT_MS != 0 {
+            tsft * 1_000
+        } else {
+            tsft
+        }),
+    }
 }
 
-pub async fn parse_service_descriptor_attribute(
-    input: &[u8],
-) -> IResult<&[u8], ServiceDescriptorAttribute> {
-    let (input, attribute_id) = le_u8(input)?;
-    let (input, attribute_length) = le_u16(input)?;
-    let (input, service_id) = take(6usize)(input)?;
-    let (input, instance_id) = le_u8(input)?;
-    let (input, requestor_id) = le_u8(input)?;
-    let (input, service_control) = le_u8(input)?;
-    let (input, service_info_length) = le_u8(input)?;
-    let (input, message_counter) = le_u8(input)?;
-
-    if service_info_length == 0 || service_info_length == 1 {
-        // create empty &[u8] if service_info_length is 0
-        let service_info: &[u8] = &[];
-        return Ok((
-            input,
-            ServiceDescriptorAttribute {
-                attribute_id,
-                attribute_length,
-                service_id: service_id.try_into().unwrap(),
-                instance_id,
-                requestor_id,
-                service_control,
-                service_info_length,
-                message_counter,
-                service_info,
-            },
-        ));
-    }
-
-    if service_info_length < 1 {
-        return Err(nom::Err::Failure(nom::error::Error::new(
-            input,
-            nom::error::ErrorKind::Verify,
-        )));
-    }
-
-    let (input, service_info) = take(service_info_length - 1)(input)?;
-
-    Ok((
-        input,
-        ServiceDescriptorAttribute {
-            attribute_id,
-            attribute_length,
-            service_id: service_id.try_into().unwrap(),
-            instance_id,
-            requestor_id,
-            service_control,
-            service_info_length,
-            message_counter,
-            service_info,
-        },
-    ))
+pub fn remove_radiotap_header(input: &[u8]) -> Result<&[u8], ParseError> {
+    parse_radiotap_header(input).map(|(_, payload)| payload)
 }
 
-pub async fn parse_action_frame(input: &[u8]) -> IResult<&[u8], ActionFrame> {
-    let (input, frame_control) = le_u16(input)?;
-    let frame_control_version = (frame_control & 0b00000011) as u8;
-    let frame_control_type = ((frame_control & 0b00001100) >> 2) as u8;
-    let frame_control_subtype = ((frame_control & 0b11110000) >> 4) as u8;
-    let (input, duration_id) = le_u16(input)?;
-    let (input, address1) = take(6usize)(input)?;
-    let (input, address2) = take(6usize)(input)?;
-    let (input, address3) = take(6usize)(input)?;
-    let (input, sequence_control) = le_u16(input)?;
-    let (input, category) = take(1usize)(input)?;
-    let (input, action) = take(1usize)(input)?;
-    let (input, oui) = take(3usize)(input)?;
-    let (input, oui_type) = take(1usize)(input)?;
-    let (input, body) = take(input.len())(input)?;
-
-    Ok((
-        input,
-        ActionFrame {
-            frame_control,
-            frame_control_version,
-            frame_control_type,
-            frame_control_subtype,
-            duration_id,
-            address1,
-            address2,
-            address3,
-            sequence_control,
-            category: category[0],
-            action: action[0],
-            oui: oui.try_into().unwrap(),
-            oui_type: oui_type[0],
-            body,
-        },
-    ))
+/// The radio metadata of a frame captured with a radiotap header, and the 802.11 frame.
+pub fn parse_radiotap_header(input: &[u8]) -> Result<(RadioMetadata, &[u8]), ParseError> {
+    match Radiotap::from_bytes(input) {
+        Ok(radiotap) if radiotap.header.length <= input.len() => Ok((
+            RadioMetadata::from(&radiotap),
+            &input[radiotap.header.length..],
+        )),
+        Ok(radiotap) => Err(ParseError::new(
+            ParseLayer::Radiotap,
+            radiotap.header.length,
+            ParseErrorReason::InvalidLength,
+        )),
+        Err(_) => Err(ParseError::new(
+            ParseLayer::Radiotap,
+            0,
+            ParseErrorReason::InvalidValue,
+        )),
+    }
 }
 
-pub async fn parse_beacon_frame(input: &[u8]) -> IResult<&[u8], WifiBeaconFrame> {
-    let (input, frame_control) = le_u16(input)?;
-    let (input, duration) = le_u16(input)?;
-    let (input, destination_addr) = take(6usize)(input)?;
-    let (input, source_addr) = take(6usize)(input)?;
-    let (input, bssid) = take(6usize)(input)?;
-    let (input, sequence_control) = le_u16(input)?;
-    let (input, _fixed_parameters) = take(12usize)(input)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odid::{parse_message_pack, OdidMessage, RemoteIdMessage};
+    use crate::test_util::read_fixture;
+    use crate::wifi::{
+        is_action_frame, is_beacon_frame, parse_action_frame, parse_beacon_frame,
+        parse_nan_action_frame, parse_service_descriptor_attribute, NAN_SERVICE_ID,
+        WIFI_ALLIANCE_OUI,
+    };
+
+    fn message_types(messages: &[OdidMessage]) -> Vec<RemoteIdMessage> {
+        messages
+            .iter()
+            .map(|message| match message {
+                OdidMessage::BasicId(_) => RemoteIdMessage::BasicId,
+                OdidMessage::Location(_) => RemoteIdMessage::Location,
+                OdidMessage::Authentication(_) => RemoteIdMessage::Authentication,
+                OdidMessage::SelfId(_) => RemoteIdMessage::SelfId,
+                OdidMessage::SystemMessage(_) => RemoteIdMessage::SystemMessage,
+                OdidMessage::OperatorId(_) => RemoteIdMessage::OperatorId,
+                OdidMessage::Unknown(_) => RemoteIdMessage::Unknown,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_radiotap_header() {
+        let wifi_data = read_fixture("fixtures/wlan_beacon_packet_data.txt").unwrap();
+
+        let (radio, payload) = parse_radiotap_header(&wifi_data).unwrap();
+
+        assert_eq!(payload.len(), wifi_data.len() - 18);
+        assert_eq!(
+            radio,
+            RadioMetadata {
+                signal: Some(-68),
+                noise: None,
+                frequency: Some(2432),
+                flags: Some(0x10),
+                antenna: Some(0),
+                tsft: None,
+            }
+        );
+        assert!(radio.has_fcs());
+        assert!(!radio.bad_fcs());
+
+        let wifi_data = read_fixture("fixtures/wifi_packet_data.txt").unwrap();
+        let (radio, _) = parse_radiotap_header(&wifi_data).unwrap();
+
+        assert_eq!(radio.signal, Some(-48));
+        assert_eq!(radio.frequency, Some(2437));
+
+        // TSFT, flags with a bad FCS, channel 149, signal, noise and antenna
+        let header = [
+            0, 0, 25, 0, 0x6b, 0x08, 0, 0, 0x40, 0xe2, 0x01, 0, 0, 0, 0, 0, 0x50, 0, 0x71, 0x16,
+            0x40, 0x01, 0xb5, 0xa1, 1,
+        ];
+        let (radio, payload) = parse_radiotap_header(&header).unwrap();
+
+        assert!(payload.is_empty());
+        assert_eq!(
+            radio,
+            RadioMetadata {
+                signal: Some(-75),
+                noise: Some(-95),
+                frequency: Some(5745),
+                flags: Some(0x50),
+                antenna: Some(1),
+                tsft: Some(123456),
+            }
+        );
+        assert!(radio.bad_fcs());
+    }
+
+    #[test]
+    fn test_fcs() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+
+        let mut frame = b"123456789".to_vec();
+        frame.extend_from_slice(&0xcbf43926u32.to_le_bytes());
+
+        assert!(fcs_matches(&frame));
+        assert!(!fcs_matches(&frame[..frame.len() - 1]));
+        assert!(!fcs_matches(&[]));
+    }
+
+    #[test]
+    fn test_parse_ppi_header_errors() {
+        // not wrapping 802.11
+        let ppi = [0, 0, 8, 0, 1, 0, 0, 0, 0x80];
+        assert_eq!(
+            parse_ppi_header(&ppi).unwrap_err().reason,
+            ParseErrorReason::UnexpectedType
+        );
+
+        // a field running past the header
+        let ppi = [0, 0, 12, 0, 105, 0, 0, 0, 2, 0, 20, 0];
+        assert_eq!(
+            parse_ppi_header(&ppi).unwrap_err().reason,
+            ParseErrorReason::InvalidLength
+        );
+
+        let ppi = [0, 0, 8, 0, 105, 0, 0, 0, 0x80];
+        let (radio, frame) = parse_ppi_header(&ppi).unwrap();
 
-    let mut tagged_parameters = input;
-    let mut vendor_specific_data: &[u8] = &[];
+        assert_eq!(radio, RadioMetadata::default());
+        assert_eq!(frame, &[0x80]);
+        assert!(parse_ppi_header(&ppi[..6]).is_err());
+    }
+
+    #[test]
+    fn test_parse_beacon_frame() {
+        let wifi_data = read_fixture("fixtures/wlan_beacon_packet_data.txt").unwrap();
 
-    while !tagged_parameters.is_empty() {
-        let (new_input, tag_number) = le_u8(tagged_parameters)?;
+        let payload = remove_radiotap_header(&wifi_data).unwrap();
 
-        if tag_number == 0xdd {
-            let (new_input, tag_length) = le_u8(new_input)?;
-            let (new_input, tag_oui) = take(3usize)(new_input)?;
+        assert!(is_beacon_frame(payload, 0));
 
-            if tag_oui == WIFI_ALLIANCE_OUI || tag_oui == ASDSTAN_OUI {
-                let (new_input, _tag_oui_type) = le_u8(new_input)?;
-                let (new_input, _message_counter) = le_u8(new_input)?;
+        let (_, beacon_frame) = parse_beacon_frame(payload).unwrap();
+        let (_, message_pack) = parse_message_pack(beacon_frame.vendor_specific_data).unwrap();
 
-                let (_, tag_data) = take(tag_length as usize - 4)(new_input)?;
+        assert_eq!(message_pack.version, 0x2);
+        assert_eq!(message_pack.single_msg_size, 0x19);
+        assert_eq!(message_pack.num_messages, 0x4);
+        assert_eq!(
+            message_types(&message_pack.messages),
+            vec![
+                RemoteIdMessage::BasicId,
+                RemoteIdMessage::SystemMessage,
+                RemoteIdMessage::Location,
+                RemoteIdMessage::SelfId,
+            ]
+        );
 
-                vendor_specific_data = tag_data;
-                tagged_parameters = new_input;
-                break;
+        match &message_pack.messages[2] {
+            OdidMessage::Location(location) => {
+                assert_eq!(location.latitude_int, 358025796);
+                assert_eq!(location.longitude_int, -907110086);
             }
+            message => panic!("Expected location, got {:?}", message),
         }
+    }
 
-        tagged_parameters = new_input;
-    }
-
-    Ok((
-        tagged_parameters,
-        WifiBeaconFrame {
-            frame_control,
-            duration,
-            destination_address: destination_addr.try_into().unwrap(),
-            source_address: source_addr.try_into().unwrap(),
-            bssid: bssid.try_into().unwrap(),
-            sequence_control,
-            vendor_specific_data,
-        },
-    ))
-}
+    #[test]
+    fn test_parse_action_frame() {
+        let wifi_data = read_fixture("fixtures/wifi_packet_data.txt").unwrap();
+
+        let payload = remove_radiotap_header(&wifi_data).unwrap();
+
+        assert!(is_action_frame(payload, 0));
+
+        let (_, action_frame) = parse_action_frame(payload).unwrap();
 
-pub async fn remove_radiotap_header(input: &[u8]) -> Option<&[u8]> {
-    let radiotap: Option<Radiotap> = match Radiotap::from_bytes(input) {
-        Ok(radiotap) => Some(radiotap),
-        Err(error) => {
-            println!(
-                "Couldn't read packet data with Radiotap: {:?}, error {error:?}",
-                &input
-            );
-            None
+        assert_eq!(action_frame.frame_control, 0xd0);
+        assert_eq!(action_frame.frame_control_version, 0x0);
+        assert_eq!(action_frame.frame_control_type, 0x0);
+        assert_eq!(action_frame.frame_control_subtype, 0xd);
+        assert_eq!(action_frame.oui, WIFI_ALLIANCE_OUI);
+        assert_eq!(action_frame.oui_type, 0x13);
+
+        let (_, service_descriptor_attribute) =
+            parse_service_descriptor_attribute(action_frame.body).unwrap();
+
+        assert_eq!(service_descriptor_attribute.attribute_id, 0x3);
+        assert_eq!(service_descriptor_attribute.service_id, NAN_SERVICE_ID);
+        assert_eq!(service_descriptor_attribute.instance_id, 0x1);
+        assert_eq!(service_descriptor_attribute.requestor_id, 0x0);
+        assert_eq!(service_descriptor_attribute.service_control, 0x10);
+
+        let (_, message_pack) =
+            parse_message_pack(service_descriptor_attribute.service_info).unwrap();
+
+        assert_eq!(message_pack.num_messages, 0x4);
+        assert_eq!(
+            message_types(&message_pack.messages),
+            vec![
+                RemoteIdMessage::BasicId,
+                RemoteIdMessage::SystemMessage,
+                RemoteIdMessage::Location,
+                RemoteIdMessage::SelfId,
+            ]
+        );
+
+        match &message_pack.messages[2] {
+            OdidMessage::Location(location) => {
+                assert_eq!(location.latitude_int, 358026271);
+                assert_eq!(location.longitude_int, -907113683);
+            }
+            message => panic!("Expected location, got {:?}", message),
         }
-    };
+    }
 
-    if let Some(radiotap) = radiotap {
-        Some(&input[radiotap.header.length..])
-    } else {
-        None
+    #[test]
+    fn test_truncated_frames_do_not_panic() {
+        for fixture in [
+            "fixtures/wlan_beacon_packet_data.txt",
+            "fixtures/wifi_packet_data.txt",
+        ] {
+            let wifi_data = read_fixture(fixture).unwrap();
+
+            for length in 0..wifi_data.len() {
+                let Ok(payload) = remove_radiotap_header(&wifi_data[..length]) else {
+                    continue;
+                };
+
+                for header_length in [0, u8::MAX] {
+                    is_beacon_frame(payload, header_length);
+                    is_action_frame(payload, header_length);
+                }
+
+                if let Ok((_, frame)) = parse_beacon_frame(payload) {
+                    let _ = parse_message_pack(frame.vendor_specific_data);
+                }
+
+                if let Ok((_, attribute)) = parse_nan_action_frame(payload) {
+                    let _ = parse_message_pack(attribute.service_info);
+                }
+            }
+        }
     }
 }
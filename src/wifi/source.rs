@@ -0,0 +1,372 @@
+use std::{
+    collections::VecDeque,
+    net::{ToSocketAddrs, UdpSocket},
+    path::Path,
+    thread,
+};
+
+use chrono::{DateTime, Utc};
+use pcap::{Active, Capture, Linktype, Offline, PacketHeader};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// pcap link-layer header type (DLT) of a captured frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkType {
+    /// Raw 802.11, with or without a trailing FCS.
+    Ieee80211,
+    Ieee80211Radiotap,
+    /// 802.11 behind a Per-Packet Information header.
+    Ppi,
+    Other(i32),
+}
+
+impl LinkType {
+    /// Whether `decode_frame` can find the 802.11 frame behind this link type.
+    pub fn is_ieee80211(&self) -> bool {
+        !matches!(self, LinkType::Other(_))
+    }
+}
+
+impl From<i32> for LinkType {
+    fn from(value: i32) -> Self {
+        match value {
+            105 => LinkType::Ieee80211,
+            127 => LinkType::Ieee80211Radiotap,
+            192 => LinkType::Ppi,
+            _ => LinkType::Other(value),
+        }
+    }
+}
+
+impl From<LinkType> for i32 {
+    fn from(value: LinkType) -> Self {
+        match value {
+            LinkType::Ieee80211 => 105,
+            LinkType::Ieee80211Radiotap => 127,
+            LinkType::Ppi => 192,
+            LinkType::Other(value) => value,
+        }
+    }
+}
+
+impl From<Linktype> for LinkType {
+    fn from(value: Linktype) -> Self {
+        LinkType::from(value.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedFrame {
+    pub timestamp: DateTime<Utc>,
+    pub link_type: LinkType,
+    pub data: Vec<u8>,
+}
+
+impl CapturedFrame {
+    pub fn new(timestamp: DateTime<Utc>, link_type: LinkType, data: Vec<u8>) -> Self {
+        CapturedFrame {
+            timestamp,
+            link_type,
+            data,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PacketSourceError {
+    #[error("PcapError: {0}")]
+    PcapError(#[from] pcap::Error),
+    #[error("StdError: {0}")]
+    StdError(#[from] std::io::Error),
+}
+
+/// Where captured frames come from. The ODID pipeline only sees `CapturedFrame`s, so a new
+/// backend only needs to implement this.
+pub trait PacketSource: Send {
+    /// Blocks until the next frame is available, `Ok(None)` once the source is exhausted.
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>, PacketSourceError>;
+}
+
+/// Frames read off a `PacketSource` by `spawn_packet_source`.
+pub type FrameReceiver = mpsc::Receiver<Result<CapturedFrame, PacketSourceError>>;
+
+// frames waiting for the pipeline before the capture thread waits in turn
+const FRAME_BUFFER: usize = 1024;
+
+/// Reads `source` on its own OS thread, since pcap blocks until a frame arrives and would
+/// otherwise hold a runtime worker per adapter. The receiver ends once the source is
+/// exhausted, after passing on the error if it failed.
+pub fn spawn_packet_source(
+    name: &str,
+    mut source: impl PacketSource + 'static,
+) -> Result<FrameReceiver, PacketSourceError> {
+    let (tx, rx) = mpsc::channel(FRAME_BUFFER);
+
+    thread::Builder::new()
+        .name(format!("capture {}", name))
+        .spawn(move || {
+            while let Some(frame) = source.next_frame().transpose() {
+                let failed = frame.is_err();
+
+                if tx.blocking_send(frame).is_err() || failed {
+                    break;
+                }
+            }
+        })?;
+
+    Ok(rx)
+}
+
+// time_t and suseconds_t are not the same width on every platform
+#[allow(clippy::unnecessary_cast)]
+fn packet_timestamp(header: &PacketHeader) -> DateTime<Utc> {
+    DateTime::from_timestamp(header.ts.tv_sec as i64, header.ts.tv_usec as u32 * 1_000)
+        .unwrap_or_else(Utc::now)
+}
+
+/// A device in monitor mode, captured with radiotap headers where the driver offers them.
+pub struct LiveCapture {
+    capture: Capture<Active>,
+}
+
+impl LiveCapture {
+    // most informative first
+    const PREFERRED_LINK_TYPES: [Linktype; 3] = [
+        Linktype::IEEE802_11_RADIOTAP,
+        Linktype::PPI,
+        Linktype::IEEE802_11,
+    ];
+
+    pub fn open(device: &str) -> Result<Self, PacketSourceError> {
+        let mut capture = Capture::from_device(device)?
+            .promisc(true)
+            .immediate_mode(true)
+            .open()?;
+
+        let link_types = capture.list_datalinks()?;
+
+        // otherwise the device's default, which frames are skipped for if unsupported
+        if let Some(link_type) = LiveCapture::PREFERRED_LINK_TYPES
+            .iter()
+            .find(|link_type| link_types.contains(link_type))
+        {
+            capture.set_datalink(*link_type)?;
+        }
+
+        Ok(LiveCapture { capture })
+    }
+
+    pub fn link_type(&self) -> LinkType {
+        LinkType::from(self.capture.get_datalink())
+    }
+}
+
+impl PacketSource for LiveCapture {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>, PacketSourceError> {
+        let link_type = self.link_type();
+
+        loop {
+            match self.capture.next_packet() {
+                Ok(packet) => {
+                    return Ok(Some(CapturedFrame::new(
+                        packet_timestamp(packet.header),
+                        link_type,
+                        packet.data.to_vec(),
+                    )))
+                }
+                Err(pcap::Error::TimeoutExpired) => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// A `.pcap`/`.pcapng` capture, timestamped by the file.
+pub struct FileCapture {
+    capture: Capture<Offline>,
+}
+
+impl FileCapture {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PacketSourceError> {
+        let capture = Capture::from_file(path)?;
+
+        Ok(FileCapture { capture })
+    }
+
+    pub fn link_type(&self) -> LinkType {
+        LinkType::from(self.capture.get_datalink())
+    }
+}
+
+impl PacketSource for FileCapture {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>, PacketSourceError> {
+        let link_type = self.link_type();
+
+        match self.capture.next_packet() {
+            Ok(packet) => Ok(Some(CapturedFrame::new(
+                packet_timestamp(packet.header),
+                link_type,
+                packet.data.to_vec(),
+            ))),
+            Err(pcap::Error::NoMorePackets) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Frames held in memory, e.g. synthetic frames in tests.
+#[derive(Debug, Default, Clone)]
+pub struct MemorySource {
+    frames: VecDeque<CapturedFrame>,
+}
+
+impl MemorySource {
+    pub fn new(frames: Vec<CapturedFrame>) -> Self {
+        MemorySource {
+            frames: frames.into(),
+        }
+    }
+}
+
+impl PacketSource for MemorySource {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>, PacketSourceError> {
+        Ok(self.frames.pop_front())
+    }
+}
+
+/// One frame per datagram, e.g. from a remote sensor forwarding its capture. Frames are
+/// timestamped on receipt.
+pub struct UdpSource {
+    socket: UdpSocket,
+    link_type: LinkType,
+    buffer: Vec<u8>,
+}
+
+impl UdpSource {
+    // large enough for any 802.11 frame plus its capture header
+    const MAX_DATAGRAM_SIZE: usize = 65535;
+
+    pub fn bind(addr: impl ToSocketAddrs, link_type: LinkType) -> Result<Self, PacketSourceError> {
+        let socket = UdpSocket::bind(addr)?;
+
+        Ok(UdpSource {
+            socket,
+            link_type,
+            buffer: vec![0; UdpSource::MAX_DATAGRAM_SIZE],
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<std::net::SocketAddr, PacketSourceError> {
+        Ok(self.socket.local_addr()?)
+    }
+}
+
+impl PacketSource for UdpSource {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>, PacketSourceError> {
+        let (length, _) = self.socket.recv_from(&mut self.buffer)?;
+
+        Ok(Some(CapturedFrame::new(
+            Utc::now(),
+            self.link_type,
+            self.buffer[..length].to_vec(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+
+    use chrono::{DateTime, Utc};
+
+    use super::{
+        spawn_packet_source, CapturedFrame, FileCapture, LinkType, MemorySource, PacketSource,
+        UdpSource,
+    };
+
+    #[test]
+    fn test_link_type() {
+        assert_eq!(LinkType::from(127), LinkType::Ieee80211Radiotap);
+        assert_eq!(LinkType::from(105), LinkType::Ieee80211);
+        assert_eq!(LinkType::from(192), LinkType::Ppi);
+        assert_eq!(LinkType::from(1), LinkType::Other(1));
+        assert_eq!(i32::from(LinkType::Ieee80211Radiotap), 127);
+        assert_eq!(i32::from(LinkType::Ppi), 192);
+
+        assert!(LinkType::Ieee80211.is_ieee80211());
+        assert!(!LinkType::Other(1).is_ieee80211());
+    }
+
+    #[test]
+    fn test_memory_source() {
+        let timestamp = DateTime::from_timestamp(1717243200, 0).unwrap();
+        let frames = vec![
+            CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, vec![1, 2, 3]),
+            CapturedFrame::new(timestamp, LinkType::Other(1), vec![4]),
+        ];
+
+        let mut source = MemorySource::new(frames.clone());
+
+        assert_eq!(source.next_frame().unwrap(), Some(frames[0].clone()));
+        assert_eq!(source.next_frame().unwrap(), Some(frames[1].clone()));
+        assert_eq!(source.next_frame().unwrap(), None);
+    }
+
+    #[test]
+    fn test_spawn_packet_source() {
+        let timestamp = DateTime::from_timestamp(1717243200, 0).unwrap();
+        let frames = vec![
+            CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, vec![1, 2, 3]),
+            CapturedFrame::new(timestamp, LinkType::Ieee80211, vec![4]),
+        ];
+
+        let mut receiver =
+            spawn_packet_source("memory", MemorySource::new(frames.clone())).unwrap();
+
+        assert_eq!(receiver.blocking_recv().unwrap().unwrap(), frames[0]);
+        assert_eq!(receiver.blocking_recv().unwrap().unwrap(), frames[1]);
+        assert!(receiver.blocking_recv().is_none());
+    }
+
+    #[test]
+    fn test_file_capture() {
+        let mut source = FileCapture::open("fixtures/odid_replay.pcap").unwrap();
+
+        assert_eq!(source.link_type(), LinkType::Ieee80211Radiotap);
+
+        let mut timestamps: Vec<DateTime<Utc>> = vec![];
+
+        while let Some(frame) = source.next_frame().unwrap() {
+            assert_eq!(frame.link_type, LinkType::Ieee80211Radiotap);
+            timestamps.push(frame.timestamp);
+        }
+
+        assert_eq!(
+            timestamps,
+            vec![
+                DateTime::from_timestamp(1717243200, 250_000_000).unwrap(),
+                DateTime::from_timestamp(1717243201, 500_000_000).unwrap(),
+                DateTime::from_timestamp(1717243202, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_udp_source() {
+        let mut source = UdpSource::bind("127.0.0.1:0", LinkType::Ieee80211Radiotap).unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        sender
+            .send_to(
+                &[0, 0, 8, 0, 0, 0, 0, 0, 0x80],
+                source.local_addr().unwrap(),
+            )
+            .unwrap();
+
+        let frame = source.next_frame().unwrap().unwrap();
+
+        assert_eq!(frame.link_type, LinkType::Ieee80211Radiotap);
+        assert_eq!(frame.data, vec![0, 0, 8, 0, 0, 0, 0, 0, 0x80]);
+    }
+}
@@ -1,276 +1,610 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
+use anyhow::Context;
 use chrono::{DateTime, Utc};
 use log::{debug, error, info, trace, warn};
-use pcap::{Capture, Device, Linktype};
+use pcap::Device;
 use sqlx::{Pool, Postgres};
 use tokio::{sync::Mutex, task::JoinHandle};
 
 use crate::{
     drone::{Drone, DroneBuilder},
-    odid::{
-        parse_basic_id, parse_location, parse_operator_id, parse_system_message, RemoteIdMessage,
-    },
+    odid::{parse_message_pack, MessagePack, OdidMessage, ProtocolVersion},
     web::{insert_drone, update_drone, DroneDto, DroneUpdate},
     wifi::{
-        enable_monitor_mode, is_action_frame, is_beacon_frame, parse_action_frame,
-        parse_beacon_frame, parse_open_drone_id_message_pack, parse_service_descriptor_attribute,
-        remove_radiotap_header, WifiOpenDroneIDMessagePack,
+        is_action_frame, is_beacon_frame, parse_beacon_frame, parse_nan_action_frame,
+        strip_link_header, FcsMode, RadioMetadata,
     },
 };
 use tokio::sync::broadcast::Sender;
 
-use super::WifiInterface;
+use super::{
+    spawn_packet_source, CapturedFrame, FileCapture, FrameReceiver, LinkType, LiveCapture,
+    WifiInterface,
+};
 
 pub async fn start_wifi_task(
-    wifi_card: String,
     db_pool: Arc<Mutex<Pool<Postgres>>>,
     drones: Arc<Mutex<HashMap<String, Drone>>>,
     tx: Arc<Mutex<Sender<DroneUpdate>>>,
     wifi_interface: Arc<Mutex<WifiInterface>>,
+    fcs_mode: FcsMode,
 ) -> anyhow::Result<()> {
-    let wifi_card = wifi_card.as_str().to_owned();
+    // the monitor interface, which can be named differently from the configured device
+    let wifi_card = wifi_interface.lock().await.name.clone();
 
-    if let Err(e) = enable_monitor_mode(&wifi_card) {
-        eprintln!("Error: {}", e);
-        return Ok(());
+    println!("Using device: {}", wifi_card);
+
+    let source = match LiveCapture::open(&wifi_card) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error opening device \"{}\": {}", wifi_card, e);
+            let devices = Device::list().unwrap();
+            let device_names = devices
+                .iter()
+                .map(|d| d.name.clone())
+                .collect::<Vec<String>>();
+
+            eprintln!("available devices: {:?}", device_names);
+            return Ok(());
+        }
+    };
+
+    let link_type = source.link_type();
+
+    if !link_type.is_ieee80211() {
+        warn!(
+            "{} captures link type {:?}, which can't be decoded",
+            wifi_card, link_type
+        );
     }
 
-    println!("Using device: {}", wifi_card);
+    let frames = spawn_packet_source(&wifi_card, source)?;
+
+    run_packet_source(
+        frames,
+        &db_pool,
+        &drones,
+        &tx,
+        Some(&wifi_interface),
+        fcs_mode,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Replays a `.pcap`/`.pcapng` capture through the same pipeline as `start_wifi_task`, with
+/// the capture timestamps as the time each message was received.
+pub async fn start_wifi_replay_task(
+    replay_file: PathBuf,
+    db_pool: Arc<Mutex<Pool<Postgres>>>,
+    drones: Arc<Mutex<HashMap<String, Drone>>>,
+    tx: Arc<Mutex<Sender<DroneUpdate>>>,
+    fcs_mode: FcsMode,
+) -> anyhow::Result<()> {
+    let source = FileCapture::open(&replay_file)
+        .with_context(|| format!("Couldn't open capture {}", replay_file.display()))?;
 
-    let mut cap = Capture::from_device(&*wifi_card)
-        .unwrap()
-        .promisc(true)
-        .immediate_mode(true)
-        .open();
-
-    if let Err(e) = cap {
-        eprintln!("error opening device \"{}\": {}", wifi_card, e);
-        let devices = Device::list().unwrap();
-        let device_names = devices
-            .iter()
-            .map(|d| d.name.clone())
-            .collect::<Vec<String>>();
-
-        eprintln!("available devices: {:?}", device_names);
-        return Ok(());
+    let link_type = source.link_type();
+
+    if !link_type.is_ieee80211() {
+        anyhow::bail!(
+            "Unsupported link type {:?} in {}, expected 802.11, radiotap or PPI",
+            link_type,
+            replay_file.display()
+        );
     }
 
-    cap.as_mut()
-        .unwrap()
-        .set_datalink(Linktype::IEEE802_11_RADIOTAP)
-        .unwrap();
+    println!("Replaying capture: {}", replay_file.display());
 
-    while let Ok(packet) = cap.as_mut().unwrap().next_packet() {
-        tokio::task::yield_now().await;
+    let frames = spawn_packet_source("replay", source)?;
 
-        trace!("Checking packet {:?}", packet.header.len);
+    let (frame_count, odid_frames) =
+        run_packet_source(frames, &db_pool, &drones, &tx, None, fcs_mode)
+            .await
+            .with_context(|| format!("Couldn't read capture {}", replay_file.display()))?;
 
-        let data = packet.data;
+    info!(
+        "Replayed {} packets from {}, {} with ODID message packs",
+        frame_count,
+        replay_file.display(),
+        odid_frames
+    );
 
-        if String::from_utf8_lossy(&data).contains("DroneBeacon") {
-            debug!("DroneBeacon found {:?}", data);
-        }
+    Ok(())
+}
 
-        let payload: Option<&[u8]> = remove_radiotap_header(data).await;
+/// Feeds every frame read off a source through the ODID pipeline until it is exhausted,
+/// returning how many frames were read and how many carried an ODID message pack.
+pub async fn run_packet_source(
+    mut frames: FrameReceiver,
+    db_pool: &Arc<Mutex<Pool<Postgres>>>,
+    drones: &Arc<Mutex<HashMap<String, Drone>>>,
+    tx: &Arc<Mutex<Sender<DroneUpdate>>>,
+    wifi_interface: Option<&Arc<Mutex<WifiInterface>>>,
+    fcs_mode: FcsMode,
+) -> anyhow::Result<(usize, usize)> {
+    let mut frame_count = 0;
+    let mut odid_frames = 0;
 
-        if payload.is_none() {
-            continue;
-        }
+    while let Some(frame) = frames.recv().await {
+        let frame = frame?;
+
+        trace!("Checking packet {:?}", frame.data.len());
+
+        frame_count += 1;
 
-        let payload = payload.unwrap();
+        if let Some(decoded_frame) = decode_frame(&frame, fcs_mode) {
+            odid_frames += 1;
 
-        if is_beacon_frame(payload, 0).await {
-            trace!("Beacon frame found");
+            if let Some(wifi_interface) = wifi_interface {
+                let mut wifi_interface = wifi_interface.lock().await;
+                wifi_interface.update_last_odid_received(frame.timestamp);
+            }
+
+            handle_message_pack(
+                decoded_frame.message_pack,
+                decoded_frame.radio,
+                frame.timestamp,
+                db_pool,
+                drones,
+                tx,
+            )
+            .await;
         }
+    }
+
+    Ok((frame_count, odid_frames))
+}
+
+/// An ODID message pack and how the frame carrying it was received.
+#[derive(Debug, Clone)]
+pub struct DecodedFrame {
+    pub message_pack: MessagePack,
+    pub radio: RadioMetadata,
+}
+
+/// The ODID message pack carried by a captured Beacon or NAN action frame, if it holds any
+/// messages.
+pub fn decode_frame(frame: &CapturedFrame, fcs_mode: FcsMode) -> Option<DecodedFrame> {
+    let data = frame.data.as_slice();
 
-        if is_action_frame(payload, 0).await {
-            trace!("Action frame found");
+    if String::from_utf8_lossy(data).contains("DroneBeacon") {
+        debug!("DroneBeacon found {:?}", data);
+    }
+
+    if let LinkType::Other(link_type) = frame.link_type {
+        trace!("Unsupported link type {}", link_type);
+        return None;
+    }
+
+    let (radio, payload) = match strip_link_header(data, frame.link_type, fcs_mode) {
+        Ok(stripped) => stripped,
+        Err(e) => {
+            trace!("{}", e);
+            return None;
         }
+    };
 
-        let odid_message_pack: Option<WifiOpenDroneIDMessagePack> = if is_action_frame(payload, 0)
-            .await
-        {
-            match parse_action_frame(payload).await {
-                Ok((_, frame)) => match parse_service_descriptor_attribute(frame.body).await {
-                    Ok((_, service_descriptor_attribute)) => {
-                        match parse_open_drone_id_message_pack(
-                            service_descriptor_attribute.service_info,
-                        )
-                        .await
-                        {
-                            Ok((_, open_drone_id_message_pack)) => Some(open_drone_id_message_pack),
-                            Err(e) => {
-                                trace!(
-                                        "[action frame] Failed to parse Open Drone ID message pack: {:?}",
-                                        e
-                                    );
-                                trace!("data: {:?}", data);
-                                None
-                            }
-                        }
-                    }
+    if is_beacon_frame(payload, 0) {
+        trace!("Beacon frame found");
+    }
+
+    if is_action_frame(payload, 0) {
+        trace!("Action frame found");
+    }
+
+    let odid_message_pack = if is_action_frame(payload, 0) {
+        // other public action frames and NAN services are rejected here
+        match parse_nan_action_frame(payload) {
+            Ok((_, service_descriptor_attribute)) => {
+                match parse_message_pack(service_descriptor_attribute.service_info) {
+                    Ok((_, open_drone_id_message_pack)) => Some(open_drone_id_message_pack),
                     Err(e) => {
-                        trace!("Failed to parse service descriptor attribute: {:?}", e);
+                        trace!("[action frame] {}", e);
+                        trace!("data: {:?}", data);
                         None
                     }
-                },
-                Err(e) => {
-                    trace!("Failed action frame: {:?}", e);
-                    None
                 }
             }
-        } else if is_beacon_frame(payload, 0).await {
-            match parse_beacon_frame(payload).await {
-                Ok((_, beacon_frame)) => {
-                    match parse_open_drone_id_message_pack(beacon_frame.vendor_specific_data).await
-                    {
-                        Ok((_, open_drone_id_message_pack)) => Some(open_drone_id_message_pack),
-                        Err(e) => {
-                            debug!(
-                                "[beacon frame] Failed to parse Open Drone ID message pack: {:?}",
-                                e
-                            );
-                            debug!("data: {:?}", data);
-                            None
-                        }
-                    }
-                }
+            Err(e) => {
+                trace!("{}", e);
+                None
+            }
+        }
+    } else if is_beacon_frame(payload, 0) {
+        match parse_beacon_frame(payload) {
+            Ok((_, beacon_frame)) => match parse_message_pack(beacon_frame.vendor_specific_data) {
+                Ok((_, open_drone_id_message_pack)) => Some(open_drone_id_message_pack),
                 Err(e) => {
-                    trace!("Failed to parse Beacon/Action frames: {:?}", e);
+                    debug!("[beacon frame] {}", e);
+                    debug!("data: {:?}", data);
                     None
                 }
+            },
+            Err(e) => {
+                trace!("{}", e);
+                None
             }
-        } else {
-            None
-        };
+        }
+    } else {
+        None
+    };
+
+    odid_message_pack
+        .filter(|odid_message_pack| !odid_message_pack.messages.is_empty())
+        .map(|message_pack| DecodedFrame {
+            message_pack,
+            radio,
+        })
+}
 
-        if odid_message_pack.is_none() {
-            continue;
+/// The drone a message pack describes and its UAS ID, or `None` without a Basic ID.
+/// Authentication pages are spread over several packs, so they are assembled on the drone
+/// already being tracked, whose progress and finished authentication carry over.
+pub fn drone_from_message_pack(
+    drones: &mut HashMap<String, Drone>,
+    odid_message_pack: MessagePack,
+    radio: RadioMetadata,
+    received: DateTime<Utc>,
+) -> Option<(String, Drone)> {
+    let mut drone: Drone = DroneBuilder::default().build().unwrap();
+    let mut pages = vec![];
+
+    drone.update_protocol_version(ProtocolVersion::from(odid_message_pack.version));
+    drone.update_radio(radio);
+
+    for message in odid_message_pack.messages {
+        match message {
+            OdidMessage::Unknown(message_type) => {
+                println!("Unknown message type: {:?}", message_type);
+            }
+            OdidMessage::Authentication(page) => pages.push(page),
+            message => drone.update_message_at(message, received),
         }
+    }
+
+    let drone_id = drone.basic_id.as_ref()?.uas_id.to_string();
 
-        let odid_message_pack = odid_message_pack.unwrap();
+    match drones.get_mut(&drone_id) {
+        Some(stored) => {
+            for page in pages {
+                stored.update_authentication_at(page, received);
+            }
 
-        if odid_message_pack.messages.len() > 0 {
-            println!("Received ODID message pack {:?}", odid_message_pack);
-        } else {
-            continue;
+            drone.authentication = stored.authentication.clone();
+            drone.authentication_assembler = stored.authentication_assembler.clone();
+        }
+        None => {
+            for page in pages {
+                drone.update_authentication_at(page, received);
+            }
         }
+    }
 
-        let current_timestamp: DateTime<Utc> = Utc::now();
+    Some((drone_id, drone))
+}
 
-        {
-            let mut wifi_interface = wifi_interface.lock().await;
-            wifi_interface.update_last_odid_received(current_timestamp);
+async fn handle_message_pack(
+    odid_message_pack: MessagePack,
+    radio: RadioMetadata,
+    received: DateTime<Utc>,
+    db_pool: &Arc<Mutex<Pool<Postgres>>>,
+    drones: &Arc<Mutex<HashMap<String, Drone>>>,
+    tx: &Arc<Mutex<Sender<DroneUpdate>>>,
+) {
+    println!("Received ODID message pack {:?}", odid_message_pack);
+
+    let (drone_id, drone) = {
+        let mut drones = drones.lock().await;
+
+        match drone_from_message_pack(&mut drones, odid_message_pack, radio, received) {
+            Some(drone) => drone,
+            None => return,
         }
+    };
 
-        let mut drone: Drone = DroneBuilder::default().build().unwrap();
+    println!("Checking payload for drone {:?}", drone);
 
-        for message in odid_message_pack.messages {
-            match RemoteIdMessage::from(message.message_type) {
-                RemoteIdMessage::SystemMessage => {
-                    if let Ok((_, system_message)) = parse_system_message(&message.message_body) {
-                        drone.update_system_message(system_message);
-                    }
-                }
-                RemoteIdMessage::BasicId => {
-                    if let Ok((_, basic_id_message)) = parse_basic_id(&message.message_body) {
-                        drone.update_basic_id(basic_id_message);
-                    }
-                }
-                RemoteIdMessage::Location => {
-                    if let Ok((_, location_message)) = parse_location(&message.message_body) {
-                        drone.update_location(location_message);
-                    }
-                }
-                RemoteIdMessage::OperatorId => {
-                    if let Ok((_, operator_id_message)) = parse_operator_id(&message.message_body) {
-                        drone.update_operator(operator_id_message);
-                    }
-                }
-                m => {
-                    println!("Unknown message type: {:?} {:?}", message.message_type, m);
-                    continue;
-                }
+    {
+        let mut drones = drones.lock().await;
+
+        if drones.contains_key(&drone_id) {
+            let drone = drones.get_mut(&drone_id).unwrap();
+
+            drone.update_radio(radio);
+
+            if let Some(last_location) = drone.last_location.clone() {
+                drone.update_location_at(last_location, received);
+            }
+
+            if let Some(system_message) = drone.system_message.clone() {
+                drone.update_system_message(system_message);
+            }
+
+            if let Some(operator) = drone.operator.clone() {
+                drone.update_operator(operator);
+            }
+
+            if let Some(self_id) = drone.self_id.clone() {
+                drone.update_self_id(self_id);
+            }
+
+            if let Some(basic_id) = drone.basic_id.clone() {
+                drone.update_basic_id(basic_id);
             }
         }
+    }
 
-        println!("Checking payload for drone {:?}", drone);
+    if drone.payload_ready() && !drone.is_in_db {
+        println!("Payload ready for drone");
 
-        let drone_id = if let Some(id) = drone.basic_id.as_ref() {
-            id.uas_id.clone()
-        } else {
-            continue;
-        };
+        println!("Drone ID: {}", drone_id);
 
         {
             let mut drones = drones.lock().await;
+            drones.insert(drone_id.clone(), drone.clone());
+        }
 
-            if drones.contains_key(&drone_id) {
-                let drone = drones.get_mut(&drone_id).unwrap();
+        let drone_dto = DroneDto::from(drone.clone());
 
-                if let Some(last_location) = drone.last_location.clone() {
-                    drone.update_location(last_location);
-                }
+        let (db_pool, tx) = {
+            let db_pool = db_pool.lock().await.clone();
+            let tx = tx.lock().await.clone();
+            (db_pool, tx)
+        };
 
-                if let Some(system_message) = drone.system_message.clone() {
-                    drone.update_system_message(system_message);
-                }
+        println!("Inserting drone into database");
 
-                if let Some(operator) = drone.operator.clone() {
-                    drone.update_operator(operator);
-                }
+        let drone_dto = insert_drone(drone_dto, &db_pool, &tx).await;
+
+        let mut drones = drones.lock().await;
+        if let Some(mut drone) = drones.get_mut(&drone_id) {
+            drone.set_in_db(true, drone_dto.id);
+        }
+    } else if drone.payload_ready() && drone.is_in_db {
+        let drone_dto = DroneDto::from(drone.clone());
 
-                if let Some(basic_id) = drone.basic_id.clone() {
-                    drone.update_basic_id(basic_id);
+        let (db_pool, tx) = {
+            let db_pool = db_pool.lock().await.clone();
+            let tx = tx.lock().await.clone();
+            (db_pool, tx)
+        };
+
+        update_drone(drone_dto, &db_pool, &tx).await;
+    } else {
+        let mut drones = drones.lock().await;
+        drones.insert(drone_id, drone);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use std::collections::HashMap;
+
+    use super::{decode_frame, drone_from_message_pack};
+    use crate::odid::{
+        AuthType, Authentication, OdidMessage, AUTH_PAGE_0_DATA_LENGTH, AUTH_PAGE_N_DATA_LENGTH,
+    };
+    use crate::test_util::read_fixture;
+    use crate::wifi::{CapturedFrame, FcsMode, FileCapture, LinkType, MemorySource, PacketSource};
+
+    fn latitudes(source: &mut dyn PacketSource) -> Vec<i32> {
+        let mut latitudes = vec![];
+
+        while let Some(frame) = source.next_frame().unwrap() {
+            if let Some(decoded_frame) = decode_frame(&frame, FcsMode::Strip) {
+                for message in decoded_frame.message_pack.messages {
+                    if let OdidMessage::Location(location) = message {
+                        latitudes.push(location.latitude_int);
+                    }
                 }
             }
         }
 
-        if drone.payload_ready() && !drone.is_in_db {
-            println!("Payload ready for drone");
+        latitudes
+    }
 
-            println!("Drone ID: {}", drone_id);
+    #[test]
+    fn test_decode_synthetic_frames() {
+        let timestamp: DateTime<Utc> = DateTime::from_timestamp(1717243200, 0).unwrap();
+        let beacon = read_fixture("fixtures/wlan_beacon_packet_data.txt").unwrap();
+        let action = read_fixture("fixtures/wifi_packet_data.txt").unwrap();
+
+        let mut source = MemorySource::new(vec![
+            CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, beacon.clone()),
+            // an unsupported link type is skipped rather than misparsed
+            CapturedFrame::new(timestamp, LinkType::Other(1), beacon.clone()),
+            CapturedFrame::new(
+                timestamp,
+                LinkType::Ieee80211Radiotap,
+                beacon[..60].to_vec(),
+            ),
+            CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, vec![]),
+            CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, action),
+        ]);
+
+        assert_eq!(latitudes(&mut source), vec![358025796, 358026271]);
+    }
 
-            {
-                let mut drones = drones.lock().await;
-                drones.insert(drone_id.clone(), drone.clone());
-            }
+    #[test]
+    fn test_decode_radio_metadata() {
+        let timestamp: DateTime<Utc> = DateTime::from_timestamp(1717243200, 0).unwrap();
+        let action = read_fixture("fixtures/wifi_packet_data.txt").unwrap();
 
-            let drone_dto = DroneDto::from(drone.clone());
+        let decoded_frame = decode_frame(
+            &CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, action),
+            FcsMode::Strip,
+        )
+        .unwrap();
 
-            let (db_pool, tx) = {
-                let db_pool = db_pool.lock().await.clone();
-                let tx = tx.lock().await.clone();
-                (db_pool, tx)
-            };
+        assert_eq!(decoded_frame.radio.signal, Some(-48));
+        assert_eq!(decoded_frame.radio.frequency, Some(2437));
+        assert!(decoded_frame.radio.has_fcs());
+    }
 
-            println!("Inserting drone into database");
+    fn ppi_frame(frame: &[u8], common_flags: u16) -> Vec<u8> {
+        let mut ppi = vec![0, 0, 32, 0, 105, 0, 0, 0];
+        // 802.11-common field: TSFT, flags, rate, channel, FHSS, signal and noise
+        ppi.extend_from_slice(&[2, 0, 20, 0]);
+        ppi.extend_from_slice(&123456u64.to_le_bytes());
+        ppi.extend_from_slice(&common_flags.to_le_bytes());
+        ppi.extend_from_slice(&[2, 0, 0x85, 0x09, 0xa0, 0, 0, 0, 0xd0, 0xa1]);
+        ppi.extend_from_slice(frame);
+        ppi
+    }
 
-            let drone_dto = insert_drone(drone_dto, &db_pool, &tx).await;
+    #[test]
+    fn test_decode_link_types() {
+        let timestamp: DateTime<Utc> = DateTime::from_timestamp(1717243200, 0).unwrap();
+        let action = read_fixture("fixtures/wifi_packet_data.txt").unwrap();
+        // the fixture's radiotap header is 18 bytes, and the frame ends with its FCS
+        let frame = &action[18..];
+        let latitude = |link_type, data: Vec<u8>, fcs_mode| {
+            let decoded_frame =
+                decode_frame(&CapturedFrame::new(timestamp, link_type, data), fcs_mode)?;
+
+            decoded_frame
+                .message_pack
+                .messages
+                .into_iter()
+                .find_map(|message| match message {
+                    OdidMessage::Location(location) => Some(location.latitude_int),
+                    _ => None,
+                })
+        };
 
-            let mut drones = drones.lock().await;
-            if let Some(mut drone) = drones.get_mut(&drone_id) {
-                drone.set_in_db(true, drone_dto.id);
-            }
-        } else if drone.payload_ready() && drone.is_in_db {
-            let drone_dto = DroneDto::from(drone.clone());
+        assert_eq!(
+            latitude(LinkType::Ieee80211, frame.to_vec(), FcsMode::Strip),
+            Some(358026271)
+        );
+        // without its FCS as well
+        assert_eq!(
+            latitude(
+                LinkType::Ieee80211,
+                frame[..frame.len() - 4].to_vec(),
+                FcsMode::Verify
+            ),
+            Some(358026271)
+        );
+
+        assert_eq!(
+            latitude(LinkType::Ppi, ppi_frame(frame, 0x1), FcsMode::Verify),
+            Some(358026271)
+        );
+
+        let decoded_frame = decode_frame(
+            &CapturedFrame::new(timestamp, LinkType::Ppi, ppi_frame(frame, 0x1)),
+            FcsMode::Strip,
+        )
+        .unwrap();
 
-            let (db_pool, tx) = {
-                let db_pool = db_pool.lock().await.clone();
-                let tx = tx.lock().await.clone();
-                (db_pool, tx)
-            };
+        assert_eq!(decoded_frame.radio.signal, Some(-48));
+        assert_eq!(decoded_frame.radio.noise, Some(-95));
+        assert_eq!(decoded_frame.radio.frequency, Some(2437));
+        assert_eq!(decoded_frame.radio.tsft, Some(123456));
+        assert!(decoded_frame.radio.has_fcs());
+    }
 
-            update_drone(drone_dto, &db_pool, &tx).await;
-        } else {
-            let mut drones = drones.lock().await;
-            drones.insert(drone_id, drone);
-        }
+    #[test]
+    fn test_decode_bad_fcs() {
+        let timestamp: DateTime<Utc> = DateTime::from_timestamp(1717243200, 0).unwrap();
+        let mut action = read_fixture("fixtures/wifi_packet_data.txt").unwrap();
+        let last = action.len() - 1;
+        action[last] ^= 0xff;
+
+        let frame = CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, action.clone());
+
+        assert!(decode_frame(&frame, FcsMode::Strip).is_some());
+        assert!(decode_frame(&frame, FcsMode::Verify).is_none());
+
+        // flagged as bad by the driver
+        let frame = CapturedFrame::new(timestamp, LinkType::Ppi, ppi_frame(&action[18..], 0x5));
 
-        // Yield control to allow other tasks to run
-        tokio::task::yield_now().await;
+        assert!(decode_frame(&frame, FcsMode::Strip).is_some());
+        assert!(decode_frame(&frame, FcsMode::Verify).is_none());
     }
 
-    Ok(())
+    #[test]
+    fn test_replay_capture() {
+        let mut source = FileCapture::open("fixtures/odid_replay.pcap").unwrap();
+
+        // the last packet is a truncated beacon, which is skipped
+        assert_eq!(latitudes(&mut source), vec![358025796, 358026271]);
+    }
+
+    #[test]
+    fn test_authentication_across_message_packs() {
+        let timestamp: DateTime<Utc> = DateTime::from_timestamp(1717243200, 0).unwrap();
+        let action = read_fixture("fixtures/wifi_packet_data.txt").unwrap();
+        let decoded_frame = decode_frame(
+            &CapturedFrame::new(timestamp, LinkType::Ieee80211Radiotap, action),
+            FcsMode::Strip,
+        )
+        .unwrap();
+
+        let with_page = |page: Authentication| {
+            let mut message_pack = decoded_frame.message_pack.clone();
+            message_pack
+                .messages
+                .push(OdidMessage::Authentication(page));
+            message_pack
+        };
+
+        let page_0 = Authentication {
+            auth_type: AuthType::MessageSetSignature,
+            page: 0,
+            last_page_index: Some(1),
+            length: Some(30),
+            timestamp: Some(1234),
+            auth_data: [0xa0; AUTH_PAGE_0_DATA_LENGTH].into_iter().collect(),
+        };
+        let page_1 = Authentication {
+            auth_type: AuthType::MessageSetSignature,
+            page: 1,
+            last_page_index: None,
+            length: None,
+            timestamp: None,
+            auth_data: [0xa1; AUTH_PAGE_N_DATA_LENGTH].into_iter().collect(),
+        };
+
+        let mut drones = HashMap::new();
+
+        let (drone_id, drone) = drone_from_message_pack(
+            &mut drones,
+            with_page(page_0),
+            decoded_frame.radio,
+            timestamp,
+        )
+        .unwrap();
+
+        assert!(drone.authentication.is_none());
+        drones.insert(drone_id, drone);
+
+        let (drone_id, drone) = drone_from_message_pack(
+            &mut drones,
+            with_page(page_1),
+            decoded_frame.radio,
+            timestamp,
+        )
+        .unwrap();
+
+        let authentication = drone.authentication.clone().unwrap();
+        assert_eq!(authentication.timestamp, 1234);
+        assert_eq!(authentication.auth_data.len(), 30);
+        drones.insert(drone_id, drone);
+
+        // a finished authentication outlives packs without pages
+        let (_, drone) = drone_from_message_pack(
+            &mut drones,
+            decoded_frame.message_pack.clone(),
+            decoded_frame.radio,
+            timestamp,
+        )
+        .unwrap();
+
+        assert_eq!(drone.authentication, Some(authentication));
+    }
 }